@@ -0,0 +1,2 @@
+ALTER TABLE applications ADD COLUMN shared BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE applications ADD COLUMN searchable BOOLEAN NOT NULL DEFAULT FALSE;
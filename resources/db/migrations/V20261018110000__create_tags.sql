@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS tags
+(
+  tag_id UUID PRIMARY KEY,
+  user_id UUID NOT NULL,
+  name TEXT NOT NULL,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+);
+
+CREATE TABLE IF NOT EXISTS bookmark_tags
+(
+  bookmark_id UUID NOT NULL,
+  tag_id UUID NOT NULL,
+
+  PRIMARY KEY (bookmark_id, tag_id),
+  FOREIGN KEY (bookmark_id) REFERENCES bookmarks(bookmark_id) ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS application_tags
+(
+  application_id UUID NOT NULL,
+  tag_id UUID NOT NULL,
+
+  PRIMARY KEY (application_id, tag_id),
+  FOREIGN KEY (application_id) REFERENCES applications(application_id) ON DELETE CASCADE,
+  FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX tags_user_name_idx ON tags(user_id, name);
+CREATE INDEX bookmark_tags_tag_idx ON bookmark_tags(tag_id);
+CREATE INDEX application_tags_tag_idx ON application_tags(tag_id);
@@ -0,0 +1,5 @@
+ALTER TABLE bookmarks ADD COLUMN created_at DATETIME;
+ALTER TABLE applications ADD COLUMN created_at DATETIME;
+
+UPDATE bookmarks SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+UPDATE applications SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
@@ -0,0 +1 @@
+ALTER TABLE applications ADD COLUMN category_id UUID REFERENCES categories(category_id);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS link_checks
+(
+  url TEXT PRIMARY KEY,
+  status INTEGER,
+  final_url TEXT,
+  permanent_redirect BOOLEAN NOT NULL DEFAULT FALSE,
+  broken BOOLEAN NOT NULL DEFAULT FALSE,
+  error TEXT,
+  checked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
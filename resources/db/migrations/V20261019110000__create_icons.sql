@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS icons
+(
+  icon_id TEXT PRIMARY KEY,
+  content_type TEXT NOT NULL,
+  content BLOB NOT NULL,
+  created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE bookmarks ADD COLUMN favicon_id TEXT REFERENCES icons(icon_id);
+ALTER TABLE applications ADD COLUMN favicon_id TEXT REFERENCES icons(icon_id);
@@ -0,0 +1,60 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS bookmarks_fts USING fts5
+(
+  name, url,
+  content='bookmarks', tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS applications_fts USING fts5
+(
+  name, description, url,
+  content='applications', tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS categories_fts USING fts5
+(
+  name,
+  content='categories', tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER bookmarks_fts_insert AFTER INSERT ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(rowid, name, url) VALUES (new.rowid, new.name, new.url);
+END;
+
+CREATE TRIGGER bookmarks_fts_delete AFTER DELETE ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(bookmarks_fts, rowid, name, url) VALUES ('delete', old.rowid, old.name, old.url);
+END;
+
+CREATE TRIGGER bookmarks_fts_update AFTER UPDATE OF name, url ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(bookmarks_fts, rowid, name, url) VALUES ('delete', old.rowid, old.name, old.url);
+  INSERT INTO bookmarks_fts(rowid, name, url) VALUES (new.rowid, new.name, new.url);
+END;
+
+CREATE TRIGGER applications_fts_insert AFTER INSERT ON applications BEGIN
+  INSERT INTO applications_fts(rowid, name, description, url) VALUES (new.rowid, new.name, new.description, new.url);
+END;
+
+CREATE TRIGGER applications_fts_delete AFTER DELETE ON applications BEGIN
+  INSERT INTO applications_fts(applications_fts, rowid, name, description, url) VALUES ('delete', old.rowid, old.name, old.description, old.url);
+END;
+
+CREATE TRIGGER applications_fts_update AFTER UPDATE OF name, description, url ON applications BEGIN
+  INSERT INTO applications_fts(applications_fts, rowid, name, description, url) VALUES ('delete', old.rowid, old.name, old.description, old.url);
+  INSERT INTO applications_fts(rowid, name, description, url) VALUES (new.rowid, new.name, new.description, new.url);
+END;
+
+CREATE TRIGGER categories_fts_insert AFTER INSERT ON categories BEGIN
+  INSERT INTO categories_fts(rowid, name) VALUES (new.rowid, new.name);
+END;
+
+CREATE TRIGGER categories_fts_delete AFTER DELETE ON categories BEGIN
+  INSERT INTO categories_fts(categories_fts, rowid, name) VALUES ('delete', old.rowid, old.name);
+END;
+
+CREATE TRIGGER categories_fts_update AFTER UPDATE OF name ON categories BEGIN
+  INSERT INTO categories_fts(categories_fts, rowid, name) VALUES ('delete', old.rowid, old.name);
+  INSERT INTO categories_fts(rowid, name) VALUES (new.rowid, new.name);
+END;
+
+INSERT INTO bookmarks_fts(bookmarks_fts) VALUES ('rebuild');
+INSERT INTO applications_fts(applications_fts) VALUES ('rebuild');
+INSERT INTO categories_fts(categories_fts) VALUES ('rebuild');
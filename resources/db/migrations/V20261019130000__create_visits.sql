@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS visits
+(
+  user_id UUID NOT NULL,
+  item_id UUID NOT NULL,
+  visited_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+);
+
+CREATE INDEX visits_item_idx ON visits(item_id, visited_at);
+
+-- frecency of visited bookmarks and applications - each visit scores the more,
+-- the more recent it is
+CREATE VIEW IF NOT EXISTS frecencies AS
+SELECT item_id,
+       sum(CASE WHEN visited_at >= datetime('now', '-4 days') THEN 100
+                WHEN visited_at >= datetime('now', '-14 days') THEN 70
+                WHEN visited_at >= datetime('now', '-31 days') THEN 50
+                WHEN visited_at >= datetime('now', '-90 days') THEN 30
+                ELSE 10 END) AS frecency
+FROM visits
+GROUP BY item_id;
+
+CREATE TRIGGER bookmarks_visits_delete AFTER DELETE ON bookmarks BEGIN
+  DELETE FROM visits WHERE item_id = old.bookmark_id;
+END;
+
+CREATE TRIGGER applications_visits_delete AFTER DELETE ON applications BEGIN
+  DELETE FROM visits WHERE item_id = old.application_id;
+END;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS archives
+(
+  bookmark_id UUID PRIMARY KEY,
+  url TEXT NOT NULL,
+  content BLOB NOT NULL,
+  size INTEGER NOT NULL,
+  captured_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  FOREIGN KEY (bookmark_id) REFERENCES bookmarks(bookmark_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- trashed categories, bookmarks and applications are kept until they get restored or purged
+ALTER TABLE categories ADD COLUMN deleted_at DATETIME;
+ALTER TABLE bookmarks ADD COLUMN deleted_at DATETIME;
+ALTER TABLE applications ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX categories_deleted_at_idx ON categories(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX bookmarks_deleted_at_idx ON bookmarks(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX applications_deleted_at_idx ON applications(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,53 @@
+ALTER TABLE bookmarks ADD COLUMN notes TEXT;
+ALTER TABLE applications ADD COLUMN notes TEXT;
+
+-- notes are searchable too, so the indexes of bookmarks and applications get recreated
+DROP TRIGGER bookmarks_fts_insert;
+DROP TRIGGER bookmarks_fts_delete;
+DROP TRIGGER bookmarks_fts_update;
+DROP TRIGGER applications_fts_insert;
+DROP TRIGGER applications_fts_delete;
+DROP TRIGGER applications_fts_update;
+DROP TABLE bookmarks_fts;
+DROP TABLE applications_fts;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS bookmarks_fts USING fts5
+(
+  name, url, notes,
+  content='bookmarks', tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS applications_fts USING fts5
+(
+  name, description, url, notes,
+  content='applications', tokenize='unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER bookmarks_fts_insert AFTER INSERT ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(rowid, name, url, notes) VALUES (new.rowid, new.name, new.url, new.notes);
+END;
+
+CREATE TRIGGER bookmarks_fts_delete AFTER DELETE ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(bookmarks_fts, rowid, name, url, notes) VALUES ('delete', old.rowid, old.name, old.url, old.notes);
+END;
+
+CREATE TRIGGER bookmarks_fts_update AFTER UPDATE OF name, url, notes ON bookmarks BEGIN
+  INSERT INTO bookmarks_fts(bookmarks_fts, rowid, name, url, notes) VALUES ('delete', old.rowid, old.name, old.url, old.notes);
+  INSERT INTO bookmarks_fts(rowid, name, url, notes) VALUES (new.rowid, new.name, new.url, new.notes);
+END;
+
+CREATE TRIGGER applications_fts_insert AFTER INSERT ON applications BEGIN
+  INSERT INTO applications_fts(rowid, name, description, url, notes) VALUES (new.rowid, new.name, new.description, new.url, new.notes);
+END;
+
+CREATE TRIGGER applications_fts_delete AFTER DELETE ON applications BEGIN
+  INSERT INTO applications_fts(applications_fts, rowid, name, description, url, notes) VALUES ('delete', old.rowid, old.name, old.description, old.url, old.notes);
+END;
+
+CREATE TRIGGER applications_fts_update AFTER UPDATE OF name, description, url, notes ON applications BEGIN
+  INSERT INTO applications_fts(applications_fts, rowid, name, description, url, notes) VALUES ('delete', old.rowid, old.name, old.description, old.url, old.notes);
+  INSERT INTO applications_fts(rowid, name, description, url, notes) VALUES (new.rowid, new.name, new.description, new.url, new.notes);
+END;
+
+INSERT INTO bookmarks_fts(bookmarks_fts) VALUES ('rebuild');
+INSERT INTO applications_fts(applications_fts) VALUES ('rebuild');
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS api_tokens
+(
+  token_id UUID PRIMARY KEY NOT NULL,
+  user_id UUID NOT NULL,
+  name TEXT NOT NULL,
+  token_hash TEXT NOT NULL UNIQUE,
+  created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_used_at DATETIME,
+
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+);
+
+CREATE INDEX api_tokens_user_idx ON api_tokens(user_id);
@@ -0,0 +1,39 @@
+ALTER TABLE users ADD COLUMN admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS groups
+(
+  group_id UUID PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX groups_name_idx ON groups(name);
+
+CREATE TABLE IF NOT EXISTS group_members
+(
+  group_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+
+  PRIMARY KEY (group_id, user_id),
+  FOREIGN KEY (group_id) REFERENCES groups(group_id) ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+-- shared applications are visible to all users, or to members of their group only
+ALTER TABLE applications ADD COLUMN group_id UUID REFERENCES groups(group_id);
+
+-- sharing applications used to have no effect and is up to admins now, none of whom
+-- exists yet, so nothing stays shared
+UPDATE applications SET shared = FALSE;
+
+-- how users see applications shared with them, without modifying the applications
+CREATE TABLE IF NOT EXISTS application_overlays
+(
+  user_id UUID NOT NULL,
+  application_id UUID NOT NULL,
+  visibility BOOLEAN,
+  position INTEGER,
+
+  PRIMARY KEY (user_id, application_id),
+  FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+  FOREIGN KEY (application_id) REFERENCES applications(application_id) ON DELETE CASCADE
+);
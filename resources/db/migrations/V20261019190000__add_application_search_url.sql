@@ -0,0 +1,5 @@
+-- template of URL searching within application, with {q} standing for searched terms
+ALTER TABLE applications ADD COLUMN search_url TEXT;
+
+-- searchable applications need a search URL, which none of them has yet
+UPDATE applications SET searchable = FALSE;
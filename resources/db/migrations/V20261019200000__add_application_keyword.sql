@@ -0,0 +1,2 @@
+-- keyword prefixing address-bar queries, which get dispatched to the application
+ALTER TABLE applications ADD COLUMN keyword TEXT;
@@ -0,0 +1,2 @@
+-- categories imported from nested folders, named by path of folders they've been flattened from
+ALTER TABLE categories ADD COLUMN nested BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,20 @@
+-- integer identifiers of bookmarks and tags exposed through linkding API, unlike rowids
+-- these are stored explicitly and don't change when database gets vacuumed
+ALTER TABLE bookmarks ADD COLUMN linkding_id INTEGER;
+ALTER TABLE tags ADD COLUMN linkding_id INTEGER;
+
+UPDATE bookmarks SET linkding_id = rowid;
+UPDATE tags SET linkding_id = rowid;
+
+CREATE UNIQUE INDEX bookmarks_linkding_id_idx ON bookmarks(linkding_id);
+CREATE UNIQUE INDEX tags_linkding_id_idx ON tags(linkding_id);
+
+CREATE TRIGGER bookmarks_linkding_id_insert AFTER INSERT ON bookmarks WHEN new.linkding_id IS NULL BEGIN
+  UPDATE bookmarks SET linkding_id = (SELECT coalesce(max(linkding_id), 0) + 1 FROM bookmarks)
+  WHERE bookmark_id = new.bookmark_id;
+END;
+
+CREATE TRIGGER tags_linkding_id_insert AFTER INSERT ON tags WHEN new.linkding_id IS NULL BEGIN
+  UPDATE tags SET linkding_id = (SELECT coalesce(max(linkding_id), 0) + 1 FROM tags)
+  WHERE tag_id = new.tag_id;
+END;
@@ -0,0 +1,2 @@
+-- tokens scoped to "search" only let browsers search user's bookmarks and applications
+ALTER TABLE api_tokens ADD COLUMN scope TEXT NOT NULL DEFAULT 'api';
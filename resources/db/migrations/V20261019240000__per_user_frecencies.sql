@@ -0,0 +1,15 @@
+-- frecency is scored per user, so that others' visits of shared applications
+-- don't affect user's own ranking
+DROP VIEW IF EXISTS frecencies;
+
+CREATE VIEW frecencies AS
+SELECT user_id, item_id,
+       sum(CASE WHEN visited_at >= datetime('now', '-4 days') THEN 100
+                WHEN visited_at >= datetime('now', '-14 days') THEN 70
+                WHEN visited_at >= datetime('now', '-31 days') THEN 50
+                WHEN visited_at >= datetime('now', '-90 days') THEN 30
+                ELSE 10 END) AS frecency
+FROM visits
+GROUP BY user_id, item_id;
+
+CREATE INDEX visits_user_item_idx ON visits(user_id, item_id, visited_at);
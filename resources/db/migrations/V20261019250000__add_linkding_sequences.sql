@@ -0,0 +1,26 @@
+-- linkding identifiers are drawn from counters which only ever grow, so that identifiers
+-- of purged bookmarks and tags are never handed out again
+CREATE TABLE IF NOT EXISTS linkding_sequences
+(
+  name TEXT NOT NULL PRIMARY KEY,
+  value INTEGER NOT NULL
+);
+
+INSERT INTO linkding_sequences(name, value)
+VALUES ('bookmarks', (SELECT coalesce(max(linkding_id), 0) FROM bookmarks)),
+       ('tags', (SELECT coalesce(max(linkding_id), 0) FROM tags));
+
+DROP TRIGGER IF EXISTS bookmarks_linkding_id_insert;
+DROP TRIGGER IF EXISTS tags_linkding_id_insert;
+
+CREATE TRIGGER bookmarks_linkding_id_insert AFTER INSERT ON bookmarks WHEN new.linkding_id IS NULL BEGIN
+  UPDATE linkding_sequences SET value = value + 1 WHERE name = 'bookmarks';
+  UPDATE bookmarks SET linkding_id = (SELECT value FROM linkding_sequences WHERE name = 'bookmarks')
+  WHERE bookmark_id = new.bookmark_id;
+END;
+
+CREATE TRIGGER tags_linkding_id_insert AFTER INSERT ON tags WHEN new.linkding_id IS NULL BEGIN
+  UPDATE linkding_sequences SET value = value + 1 WHERE name = 'tags';
+  UPDATE tags SET linkding_id = (SELECT value FROM linkding_sequences WHERE name = 'tags')
+  WHERE tag_id = new.tag_id;
+END;
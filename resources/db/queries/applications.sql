@@ -1,5 +1,72 @@
 -- :name fetch_applications_for_user_id :<> :*
--- :doc Fetches user's defined applications
-SELECT application_id, name, description, url, icon, visibility, position 
-FROM applications
-WHERE user_id = $1
+-- :doc Fetches user's defined applications along with the ones shared with user, optionally
+-- narrowed down to ones tagged with given tag. Applications of trashed categories are not
+-- grouped in any category. Shared applications are hidden and ordered by user's overlay.
+SELECT a.application_id, a.name, a.description, a.url, a.icon, a.notes, a.shared, a.group_id, a.searchable, a.search_url, a.keyword, a.created_at,
+       coalesce(o.visibility, a.visibility) AS visibility,
+       coalesce(o.position, a.position) AS position,
+       a.user_id <> $1 AS managed,
+       coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = a.url), FALSE) AS broken,
+       '/icons/' || a.favicon_id AS favicon,
+       coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $1 AND f.item_id = a.application_id), 0) AS frecency,
+       (SELECT c.category_id FROM categories c
+        WHERE c.category_id = a.category_id AND c.user_id = $1 AND c.deleted_at IS NULL) AS category_id
+FROM applications a
+LEFT JOIN application_overlays o ON o.application_id = a.application_id AND o.user_id = $1
+WHERE a.deleted_at IS NULL
+  AND (a.user_id = $1
+       OR (a.shared AND (a.group_id IS NULL
+                         OR a.group_id IN (SELECT gm.group_id FROM group_members gm WHERE gm.user_id = $1))))
+  AND ($2 IS NULL OR a.application_id IN (SELECT at.application_id
+                                          FROM application_tags at JOIN tags t ON t.tag_id = at.tag_id
+                                          WHERE t.user_id = $1 AND t.name = $2))
+ORDER BY coalesce(o.position, a.position)
+
+-- :name fetch_application_by_id :<> :?
+-- :doc Fetches user's application, or the one shared with user, by its identifier
+SELECT a.application_id, a.name, a.description, a.url, a.icon, a.notes, a.shared, a.group_id, a.searchable, a.search_url, a.keyword, a.created_at,
+       coalesce(o.visibility, a.visibility) AS visibility,
+       coalesce(o.position, a.position) AS position,
+       a.user_id <> $2 AS managed,
+       coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = a.url), FALSE) AS broken,
+       '/icons/' || a.favicon_id AS favicon,
+       coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $2 AND f.item_id = a.application_id), 0) AS frecency,
+       (SELECT c.category_id FROM categories c
+        WHERE c.category_id = a.category_id AND c.user_id = $2 AND c.deleted_at IS NULL) AS category_id
+FROM applications a
+LEFT JOIN application_overlays o ON o.application_id = a.application_id AND o.user_id = $2
+WHERE a.application_id = $1 AND a.deleted_at IS NULL
+  AND (a.user_id = $2
+       OR (a.shared AND (a.group_id IS NULL
+                         OR a.group_id IN (SELECT gm.group_id FROM group_members gm WHERE gm.user_id = $2))))
+
+-- :name create_new_application :1
+-- :doc Creates a new application for given user_id
+INSERT INTO applications(application_id, user_id, name, description, url, icon, visibility, shared, searchable, category_id, created_at, notes, group_id, search_url, keyword, position)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CURRENT_TIMESTAMP, $11, $12, $13, $14,
+        (select coalesce(max(position)+1, 0) from applications where user_id=$2))
+RETURNING application_id, position, created_at,
+          coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = applications.url), FALSE) AS broken
+
+-- :name update_application
+-- :doc Updates user's application
+UPDATE applications
+SET name=$3, description=$4, url=$5, icon=$6, visibility=$7, shared=$8, searchable=$9, category_id=$10, notes=$11, group_id=$12, search_url=$13, keyword=$14
+WHERE application_id=$1 AND user_id=$2 AND deleted_at IS NULL
+
+-- :name delete_application
+-- :doc Moves user's application to trash
+UPDATE applications SET deleted_at=CURRENT_TIMESTAMP
+WHERE application_id=$1 AND user_id=$2 AND deleted_at IS NULL
+
+-- :name upsert_application_overlay
+-- :doc Hides or reorders application shared with user, keeping overlay's properties not given
+INSERT INTO application_overlays(user_id, application_id, visibility, position)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT(user_id, application_id) DO UPDATE
+SET visibility = coalesce(excluded.visibility, visibility),
+    position = coalesce(excluded.position, position)
+
+-- :name delete_application_overlay
+-- :doc Reverts application shared with user to how it's been shared
+DELETE FROM application_overlays WHERE user_id = $1 AND application_id = $2
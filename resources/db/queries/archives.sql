@@ -0,0 +1,18 @@
+-- :name upsert_archive :1
+-- :doc Stores compressed snapshot of bookmarked page, replacing the previous one
+INSERT INTO archives(bookmark_id, url, content, size, captured_at)
+VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+ON CONFLICT (bookmark_id) DO UPDATE
+SET url=EXCLUDED.url, content=EXCLUDED.content, size=EXCLUDED.size, captured_at=EXCLUDED.captured_at
+RETURNING captured_at
+
+-- :name fetch_archive_by_bookmark_id :<> :?
+-- :doc Fetches snapshot of user's bookmark
+SELECT a.content, a.size, a.captured_at
+FROM archives a JOIN bookmarks b ON b.bookmark_id = a.bookmark_id
+WHERE a.bookmark_id = $1 AND b.user_id = $2 AND b.deleted_at IS NULL
+
+-- :name delete_archive
+-- :doc Deletes snapshot of user's bookmark
+DELETE FROM archives
+WHERE bookmark_id = $1 AND bookmark_id IN (SELECT bookmark_id FROM bookmarks WHERE user_id = $2 AND deleted_at IS NULL)
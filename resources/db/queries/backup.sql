@@ -0,0 +1,78 @@
+-- :name fetch_bookmark_tags_for_user_id :<> :*
+-- :doc Fetches names of tags assigned to all user's bookmarks
+SELECT bt.bookmark_id AS item_id, t.name
+FROM tags t JOIN bookmark_tags bt ON bt.tag_id = t.tag_id
+           JOIN bookmarks b ON b.bookmark_id = bt.bookmark_id
+WHERE t.user_id = $1 AND b.deleted_at IS NULL
+ORDER BY t.name
+
+-- :name fetch_application_tags_for_user_id :<> :*
+-- :doc Fetches names of tags assigned to all user's applications
+SELECT at.application_id AS item_id, t.name
+FROM tags t JOIN application_tags at ON at.tag_id = t.tag_id
+           JOIN applications a ON a.application_id = at.application_id
+WHERE t.user_id = $1 AND a.deleted_at IS NULL
+ORDER BY t.name
+
+-- :name fetch_bookmark_urls_for_user_id :<> :*
+-- :doc Fetches URLs of all user's bookmarks
+SELECT url FROM bookmarks WHERE user_id = $1 AND deleted_at IS NULL
+
+-- :name fetch_application_urls_for_user_id :<> :*
+-- :doc Fetches URLs of all user's applications
+SELECT url FROM applications WHERE user_id = $1 AND deleted_at IS NULL
+
+-- :name fetch_category_by_name :<> :?
+-- :doc Fetches user's category by its name
+SELECT category_id, name, position, nested FROM categories WHERE name = $1 AND user_id = $2 AND deleted_at IS NULL
+
+-- :name delete_bookmarks_for_user_id
+-- :doc Deletes all user's bookmarks
+DELETE FROM bookmarks WHERE user_id = $1
+
+-- :name delete_applications_for_user_id
+-- :doc Deletes all user's applications
+DELETE FROM applications WHERE user_id = $1
+
+-- :name delete_categories_for_user_id
+-- :doc Deletes all user's categories
+DELETE FROM categories WHERE user_id = $1
+
+-- :name delete_tags_for_user_id
+-- :doc Deletes all user's tags
+DELETE FROM tags WHERE user_id = $1
+
+-- :name restore_category
+-- :doc Restores user's category. Category with no position is put at the end.
+INSERT INTO categories(category_id, user_id, name, position, nested)
+VALUES ($1, $2, $3, coalesce($4, (select coalesce(max(position)+1, 0) from categories where user_id=$2)), $5)
+
+-- :name restore_application
+-- :doc Restores user's application. Application with no position is put at the end.
+-- Application shared with a group that no longer exists doesn't get shared at all.
+INSERT INTO applications(application_id, user_id, category_id, name, description, url, icon, visibility, shared, searchable, created_at, position, notes, group_id, search_url, keyword)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8,
+        $9 AND ($14 IS NULL OR $14 IN (SELECT group_id FROM groups)),
+        $10, coalesce($11, CURRENT_TIMESTAMP),
+        coalesce($12, (select coalesce(max(position)+1, 0) from applications where user_id=$2)), $13,
+        CASE WHEN $9 THEN (SELECT group_id FROM groups WHERE group_id = $14) END, $15, $16)
+
+-- :name restore_bookmark
+-- :doc Restores user's bookmark. Bookmark with no position is put at the end of its category.
+INSERT INTO bookmarks(bookmark_id, user_id, category_id, name, url, icon, visibility, created_at, position, notes)
+VALUES ($1, $2, $3, $4, $5, $6, $7, coalesce($8, CURRENT_TIMESTAMP),
+        coalesce($9, (select coalesce(max(position)+1, 0) from bookmarks where category_id=$3)), $10)
+
+-- :name restore_tag :1
+-- :doc Creates a new tag for given user_id or returns the existing one
+INSERT INTO tags(tag_id, user_id, name) VALUES ($1, $2, $3)
+ON CONFLICT (user_id, name) DO UPDATE SET name=EXCLUDED.name
+RETURNING tag_id
+
+-- :name restore_bookmark_tag
+-- :doc Assigns a tag to bookmark
+INSERT OR IGNORE INTO bookmark_tags(bookmark_id, tag_id) VALUES ($1, $2)
+
+-- :name restore_application_tag
+-- :doc Assigns a tag to application
+INSERT OR IGNORE INTO application_tags(application_id, tag_id) VALUES ($1, $2)
@@ -1,5 +1,50 @@
 -- :name fetch_bookmarks_for_user_id :<> :*
--- :doc Fetches user's defined bookmarks
-SELECT bookmark_id, name, url, icon, visibility, position, category_id
+-- :doc Fetches user's defined bookmarks, optionally narrowed down to ones tagged with given tag
+SELECT bookmark_id, name, url, icon, notes, visibility, position, category_id, created_at,
+       coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = bookmarks.url), FALSE) AS broken,
+       '/icons/' || favicon_id AS favicon,
+       coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $1 AND f.item_id = bookmarks.bookmark_id), 0) AS frecency,
+       (SELECT a.captured_at FROM archives a WHERE a.bookmark_id = bookmarks.bookmark_id) AS archived_at
 FROM bookmarks
-WHERE user_id = $1
+WHERE user_id = $1 AND deleted_at IS NULL
+  AND ($2 IS NULL OR bookmark_id IN (SELECT bt.bookmark_id
+                                     FROM bookmark_tags bt JOIN tags t ON t.tag_id = bt.tag_id
+                                     WHERE t.user_id = $1 AND t.name = $2))
+ORDER BY category_id, position
+
+-- :name fetch_bookmark_by_id :<> :?
+-- :doc Fetches user's bookmark by its identifier
+SELECT bookmark_id, name, url, icon, notes, visibility, position, category_id, created_at,
+       coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = bookmarks.url), FALSE) AS broken,
+       '/icons/' || favicon_id AS favicon,
+       coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $2 AND f.item_id = bookmarks.bookmark_id), 0) AS frecency,
+       (SELECT a.captured_at FROM archives a WHERE a.bookmark_id = bookmarks.bookmark_id) AS archived_at
+FROM bookmarks
+WHERE bookmark_id = $1 AND user_id = $2 AND deleted_at IS NULL
+
+-- :name create_new_bookmark :1
+-- :doc Creates a new bookmark at the end of given category
+INSERT INTO bookmarks(bookmark_id, user_id, category_id, name, url, icon, visibility, created_at, notes, position)
+VALUES ($1, $2, $3, $4, $5, $6, $7, coalesce($8, CURRENT_TIMESTAMP), $9,
+        (select coalesce(max(position)+1, 0) from bookmarks where category_id=$3))
+RETURNING bookmark_id, position, created_at,
+          coalesce((SELECT lc.broken FROM link_checks lc WHERE lc.url = bookmarks.url), FALSE) AS broken
+
+-- :name update_bookmark
+-- :doc Updates user's bookmark. Moving bookmark to other category puts it at the end.
+UPDATE bookmarks
+SET name=$3, url=$4, icon=$5, visibility=$6, notes=$8,
+    position=CASE WHEN category_id=$7 THEN position
+             ELSE (select coalesce(max(position)+1, 0) from bookmarks where category_id=$7) END,
+    category_id=$7
+WHERE bookmark_id=$1 AND user_id=$2 AND deleted_at IS NULL
+
+-- :name update_bookmark_visibility
+-- :doc Shows or hides user's bookmark
+UPDATE bookmarks SET visibility=$3
+WHERE bookmark_id=$1 AND user_id=$2 AND deleted_at IS NULL
+
+-- :name delete_bookmark
+-- :doc Moves user's bookmark to trash
+UPDATE bookmarks SET deleted_at=CURRENT_TIMESTAMP
+WHERE bookmark_id=$1 AND user_id=$2 AND deleted_at IS NULL
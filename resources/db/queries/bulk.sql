@@ -0,0 +1,32 @@
+-- :name move_bookmark
+-- :doc Moves user's bookmark to the end of given category
+UPDATE bookmarks
+SET position=CASE WHEN category_id=$3 THEN position
+             ELSE (select coalesce(max(position)+1, 0) from bookmarks where category_id=$3) END,
+    category_id=$3
+WHERE bookmark_id=$1 AND user_id=$2
+
+-- :name move_application
+-- :doc Moves user's application to given category, or out of any category
+UPDATE applications SET category_id=$3
+WHERE application_id=$1 AND user_id=$2
+
+-- :name update_bookmark_visibility
+-- :doc Shows or hides user's bookmark
+UPDATE bookmarks SET visibility=$3
+WHERE bookmark_id=$1 AND user_id=$2
+
+-- :name update_application_visibility
+-- :doc Shows or hides user's application
+UPDATE applications SET visibility=$3
+WHERE application_id=$1 AND user_id=$2
+
+-- :name delete_bookmark
+-- :doc Moves user's bookmark to trash
+UPDATE bookmarks SET deleted_at=CURRENT_TIMESTAMP
+WHERE bookmark_id=$1 AND user_id=$2
+
+-- :name delete_application
+-- :doc Moves user's application to trash
+UPDATE applications SET deleted_at=CURRENT_TIMESTAMP
+WHERE application_id=$1 AND user_id=$2
@@ -1,12 +1,34 @@
 -- :name fetch_categories_for_user_id :<> :*
 -- :doc Fetches user's defined categories
-SELECT category_id, name, position
+SELECT category_id, name, position, nested
 FROM categories
-WHERE user_id = $1
+WHERE user_id = $1 AND deleted_at IS NULL
 ORDER by position
 
 -- :name create_new_category :1
 -- :doc Creates a new category for given user_id
-INSERT INTO categories(category_id, user_id, name, position)
-VALUES ($1, $2, $3, (select coalesce(max(position)+1, 0) from categories where user_id=$2))
+INSERT INTO categories(category_id, user_id, name, position, nested)
+VALUES ($1, $2, $3, (select coalesce(max(position)+1, 0) from categories where user_id=$2), $4)
 RETURNING category_id, position
+
+-- :name fetch_category_by_id :<> :?
+-- :doc Fetches user's category by its identifier
+SELECT category_id, name, position, nested
+FROM categories
+WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NULL
+
+-- :name fetch_category_by_name :<> :?
+-- :doc Fetches user's category by its name
+SELECT category_id, name, position, nested
+FROM categories
+WHERE name = $1 AND user_id = $2 AND deleted_at IS NULL
+
+-- :name delete_category
+-- :doc Moves user's category to trash
+UPDATE categories SET deleted_at=CURRENT_TIMESTAMP
+WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NULL
+
+-- :name delete_category_bookmarks
+-- :doc Moves bookmarks of user's trashed category to trash, along with the category
+UPDATE bookmarks SET deleted_at=(SELECT deleted_at FROM categories WHERE category_id = $1)
+WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NULL
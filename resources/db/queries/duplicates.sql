@@ -0,0 +1,52 @@
+-- :name merge_bookmark_tags
+-- :doc Tags user's bookmark with all the tags of another one
+INSERT OR IGNORE INTO bookmark_tags(bookmark_id, tag_id)
+SELECT $1, tag_id FROM bookmark_tags WHERE bookmark_id = $2
+
+-- :name merge_application_tags
+-- :doc Tags user's application with all the tags of another one
+INSERT OR IGNORE INTO application_tags(application_id, tag_id)
+SELECT $1, tag_id FROM application_tags WHERE application_id = $2
+
+-- :name move_visits
+-- :doc Reassigns user's visits of one item to another
+UPDATE visits SET item_id = $1 WHERE item_id = $2 AND user_id = $3
+
+-- :name move_archive
+-- :doc Reassigns snapshot of bookmarked page to another bookmark, unless it has one already
+UPDATE archives SET bookmark_id = $1
+WHERE bookmark_id = $2 AND NOT EXISTS (SELECT 1 FROM archives WHERE bookmark_id = $1)
+
+-- :name merge_bookmark_favicon
+-- :doc Assigns favicon of another bookmark to user's bookmark which has none
+UPDATE bookmarks
+SET favicon_id = (SELECT favicon_id FROM bookmarks WHERE bookmark_id = $2 AND user_id = $3)
+WHERE bookmark_id = $1 AND user_id = $3 AND favicon_id IS NULL
+
+-- :name merge_application_favicon
+-- :doc Assigns favicon of another application to user's application which has none
+UPDATE applications
+SET favicon_id = (SELECT favicon_id FROM applications WHERE application_id = $2 AND user_id = $3)
+WHERE application_id = $1 AND user_id = $3 AND favicon_id IS NULL
+
+-- :name merge_bookmark_notes
+-- :doc Appends notes of another bookmark to notes of user's bookmark
+UPDATE bookmarks
+SET notes = coalesce(notes || char(10) || char(10), '') || (SELECT notes FROM bookmarks WHERE bookmark_id = $2 AND user_id = $3)
+WHERE bookmark_id = $1 AND user_id = $3
+  AND (SELECT notes FROM bookmarks WHERE bookmark_id = $2 AND user_id = $3) IS NOT NULL
+
+-- :name merge_application_notes
+-- :doc Appends notes of another application to notes of user's application
+UPDATE applications
+SET notes = coalesce(notes || char(10) || char(10), '') || (SELECT notes FROM applications WHERE application_id = $2 AND user_id = $3)
+WHERE application_id = $1 AND user_id = $3
+  AND (SELECT notes FROM applications WHERE application_id = $2 AND user_id = $3) IS NOT NULL
+
+-- :name delete_bookmark
+-- :doc Deletes user's bookmark merged into another one
+DELETE FROM bookmarks WHERE bookmark_id = $1 AND user_id = $2
+
+-- :name delete_application
+-- :doc Deletes user's application merged into another one
+DELETE FROM applications WHERE application_id = $1 AND user_id = $2
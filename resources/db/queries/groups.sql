@@ -0,0 +1,46 @@
+-- :name fetch_groups :<> :*
+-- :doc Fetches all groups
+SELECT group_id, name FROM groups
+ORDER BY name
+
+-- :name fetch_groups_for_user_id :<> :*
+-- :doc Fetches groups given user is a member of
+SELECT g.group_id, g.name FROM groups g
+JOIN group_members gm ON gm.group_id = g.group_id
+WHERE gm.user_id = $1
+ORDER BY g.name
+
+-- :name fetch_group_by_id :<> :?
+-- :doc Fetches group by its identifier
+SELECT group_id, name FROM groups WHERE group_id = $1
+
+-- :name fetch_group_by_name :<> :?
+-- :doc Fetches group by its name
+SELECT group_id, name FROM groups WHERE name = $1
+
+-- :name create_group
+-- :doc Creates a new group
+INSERT INTO groups(group_id, name) VALUES ($1, $2)
+
+-- :name unshare_group_applications
+-- :doc Stops sharing applications shared with given group
+UPDATE applications SET shared = FALSE, group_id = NULL WHERE group_id = $1
+
+-- :name delete_group
+-- :doc Deletes group along with its memberships
+DELETE FROM groups WHERE group_id = $1
+
+-- :name fetch_group_members :<> :*
+-- :doc Fetches members of given group
+SELECT u.user_id, u.email, u.name FROM group_members gm
+JOIN users u ON u.user_id = gm.user_id
+WHERE gm.group_id = $1
+ORDER BY u.email
+
+-- :name add_group_member
+-- :doc Adds user to group
+INSERT OR IGNORE INTO group_members(group_id, user_id) VALUES ($1, $2)
+
+-- :name remove_group_member
+-- :doc Removes user from group
+DELETE FROM group_members WHERE group_id = $1 AND user_id = $2
@@ -0,0 +1,17 @@
+-- :name insert_icon
+-- :doc Stores icon of given content hash, unless it's been already stored
+INSERT OR IGNORE INTO icons(icon_id, content_type, content) VALUES ($1, $2, $3)
+
+-- :name fetch_icon_by_id :<> :?
+-- :doc Fetches icon by its content hash
+SELECT icon_id, content_type, content
+FROM icons
+WHERE icon_id = $1
+
+-- :name update_bookmark_favicon
+-- :doc Assigns favicon to bookmark
+UPDATE bookmarks SET favicon_id=$2 WHERE bookmark_id=$1
+
+-- :name update_application_favicon
+-- :doc Assigns favicon to application
+UPDATE applications SET favicon_id=$2 WHERE application_id=$1
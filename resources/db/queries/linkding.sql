@@ -0,0 +1,46 @@
+-- :name fetch_bookmarks_for_user_id :<> :*
+-- :doc Fetches user's shown or hidden bookmarks along with their linkding identifiers and tags, newest first
+SELECT b.linkding_id, b.bookmark_id, b.name, b.url, b.notes, b.visibility, b.created_at,
+       '/icons/' || b.favicon_id AS favicon,
+       (SELECT group_concat(t.name, ' ')
+        FROM bookmark_tags bt JOIN tags t ON t.tag_id = bt.tag_id
+        WHERE bt.bookmark_id = b.bookmark_id) AS tag_names
+FROM bookmarks b
+WHERE b.user_id = $1 AND b.visibility = $2 AND b.deleted_at IS NULL
+ORDER BY b.created_at DESC, b.linkding_id DESC
+
+-- :name fetch_bookmark_by_linkding_id :<> :?
+-- :doc Fetches user's bookmark by its linkding identifier
+SELECT b.linkding_id, b.bookmark_id, b.name, b.url, b.notes, b.visibility, b.created_at,
+       '/icons/' || b.favicon_id AS favicon,
+       (SELECT group_concat(t.name, ' ')
+        FROM bookmark_tags bt JOIN tags t ON t.tag_id = bt.tag_id
+        WHERE bt.bookmark_id = b.bookmark_id) AS tag_names
+FROM bookmarks b
+WHERE b.linkding_id = $1 AND b.user_id = $2 AND b.deleted_at IS NULL
+
+-- :name fetch_bookmark_by_id :<> :?
+-- :doc Fetches user's bookmark by its identifier
+SELECT b.linkding_id, b.bookmark_id, b.name, b.url, b.notes, b.visibility, b.created_at,
+       '/icons/' || b.favicon_id AS favicon,
+       (SELECT group_concat(t.name, ' ')
+        FROM bookmark_tags bt JOIN tags t ON t.tag_id = bt.tag_id
+        WHERE bt.bookmark_id = b.bookmark_id) AS tag_names
+FROM bookmarks b
+WHERE b.bookmark_id = $1 AND b.user_id = $2 AND b.deleted_at IS NULL
+
+-- :name fetch_tags_for_user_id :<> :*
+-- :doc Fetches user's tags along with their linkding identifiers
+SELECT linkding_id, name FROM tags
+WHERE user_id = $1
+ORDER BY name
+
+-- :name fetch_tag_by_linkding_id :<> :?
+-- :doc Fetches user's tag by its linkding identifier
+SELECT linkding_id, name FROM tags
+WHERE linkding_id = $1 AND user_id = $2
+
+-- :name fetch_tag_by_id :<> :1
+-- :doc Fetches tag by its identifier
+SELECT linkding_id, name FROM tags
+WHERE tag_id = $1
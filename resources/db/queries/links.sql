@@ -0,0 +1,36 @@
+-- :name fetch_stale_urls :<> :*
+-- :doc Fetches URLs of bookmarks and applications which haven't been checked for given number of seconds
+SELECT u.url
+FROM (SELECT url FROM bookmarks WHERE deleted_at IS NULL
+      UNION SELECT url FROM applications WHERE deleted_at IS NULL) u
+     LEFT JOIN link_checks lc ON lc.url = u.url
+WHERE lc.checked_at IS NULL OR lc.checked_at < datetime('now', '-' || $1 || ' seconds')
+ORDER BY lc.checked_at NULLS FIRST
+LIMIT $2
+
+-- :name upsert_link_check
+-- :doc Records result of link check
+INSERT INTO link_checks(url, status, final_url, permanent_redirect, broken, error, checked_at)
+VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+ON CONFLICT (url) DO UPDATE
+SET status=EXCLUDED.status, final_url=EXCLUDED.final_url, permanent_redirect=EXCLUDED.permanent_redirect,
+    broken=EXCLUDED.broken, error=EXCLUDED.error, checked_at=EXCLUDED.checked_at
+
+-- :name fetch_link_check_by_url :<> :?
+-- :doc Fetches result of recent check of given URL
+SELECT url, status, final_url, permanent_redirect, broken, error, checked_at
+FROM link_checks
+WHERE url = $1
+
+-- :name fetch_link_checks_for_user_id :<> :*
+-- :doc Fetches results of link checks of user's bookmarks and applications, optionally only the broken ones
+SELECT 'bookmark' AS kind, b.bookmark_id AS id, b.name, lc.url, lc.status, lc.final_url,
+       lc.permanent_redirect, lc.broken, lc.error, lc.checked_at
+FROM bookmarks b JOIN link_checks lc ON lc.url = b.url
+WHERE b.user_id = $1 AND b.deleted_at IS NULL AND ($2 = FALSE OR lc.broken)
+UNION ALL
+SELECT 'application' AS kind, a.application_id AS id, a.name, lc.url, lc.status, lc.final_url,
+       lc.permanent_redirect, lc.broken, lc.error, lc.checked_at
+FROM applications a JOIN link_checks lc ON lc.url = a.url
+WHERE a.user_id = $1 AND a.deleted_at IS NULL AND ($2 = FALSE OR lc.broken)
+ORDER BY kind, name
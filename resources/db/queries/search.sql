@@ -0,0 +1,41 @@
+-- :name search_for_user_id :<> :*
+-- :doc Searches user's bookmarks, applications and categories matching given full-text query, the best
+-- (or optionally the most frecent) matches first
+SELECT kind, id, name, description, url, name_highlight, description_highlight, url_highlight, notes_highlight,
+       rank, frecency
+FROM (
+  SELECT 'bookmark' AS kind, b.bookmark_id AS id, b.name, NULL AS description, b.url,
+         highlight(bookmarks_fts, 0, $3, $4) AS name_highlight,
+         NULL AS description_highlight,
+         highlight(bookmarks_fts, 1, $3, $4) AS url_highlight,
+         snippet(bookmarks_fts, 2, $3, $4, '…', 16) AS notes_highlight,
+         bm25(bookmarks_fts, 10.0, 1.0, 2.0) AS rank,
+         coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $2 AND f.item_id = b.bookmark_id), 0) AS frecency
+  FROM bookmarks_fts JOIN bookmarks b ON b.rowid = bookmarks_fts.rowid
+  WHERE bookmarks_fts MATCH $1 AND b.user_id = $2 AND b.deleted_at IS NULL
+  UNION ALL
+  SELECT 'application' AS kind, a.application_id AS id, a.name, a.description, a.url,
+         highlight(applications_fts, 0, $3, $4) AS name_highlight,
+         highlight(applications_fts, 1, $3, $4) AS description_highlight,
+         highlight(applications_fts, 2, $3, $4) AS url_highlight,
+         snippet(applications_fts, 3, $3, $4, '…', 16) AS notes_highlight,
+         bm25(applications_fts, 10.0, 2.0, 1.0, 2.0) AS rank,
+         coalesce((SELECT f.frecency FROM frecencies f WHERE f.user_id = $2 AND f.item_id = a.application_id), 0) AS frecency
+  FROM applications_fts JOIN applications a ON a.rowid = applications_fts.rowid
+  WHERE applications_fts MATCH $1 AND a.deleted_at IS NULL
+    AND (a.user_id = $2
+         OR (a.shared AND (a.group_id IS NULL
+                           OR a.group_id IN (SELECT gm.group_id FROM group_members gm WHERE gm.user_id = $2))))
+  UNION ALL
+  SELECT 'category' AS kind, c.category_id AS id, c.name, NULL AS description, NULL AS url,
+         highlight(categories_fts, 0, $3, $4) AS name_highlight,
+         NULL AS description_highlight,
+         NULL AS url_highlight,
+         NULL AS notes_highlight,
+         bm25(categories_fts, 10.0) AS rank,
+         0 AS frecency
+  FROM categories_fts JOIN categories c ON c.rowid = categories_fts.rowid
+  WHERE categories_fts MATCH $1 AND c.user_id = $2 AND c.deleted_at IS NULL
+)
+ORDER BY CASE WHEN $6 THEN frecency END DESC, rank
+LIMIT $5
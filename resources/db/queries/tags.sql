@@ -0,0 +1,53 @@
+-- :name fetch_tags_for_user_id :<> :*
+-- :doc Fetches user's tags along with number of tagged bookmarks and applications
+SELECT t.tag_id, t.name,
+       (SELECT count(*) FROM bookmark_tags bt JOIN bookmarks b ON b.bookmark_id = bt.bookmark_id
+        WHERE bt.tag_id = t.tag_id AND b.deleted_at IS NULL) AS bookmarks,
+       (SELECT count(*) FROM application_tags at JOIN applications a ON a.application_id = at.application_id
+        WHERE at.tag_id = t.tag_id AND a.deleted_at IS NULL) AS applications
+FROM tags t
+WHERE t.user_id = $1
+ORDER BY t.name
+
+-- :name fetch_tags_for_bookmark_id :<> :*
+-- :doc Fetches tags assigned to given bookmark
+SELECT t.tag_id, t.name
+FROM tags t JOIN bookmark_tags bt ON bt.tag_id = t.tag_id
+WHERE bt.bookmark_id = $1 AND t.user_id = $2
+ORDER BY t.name
+
+-- :name fetch_tags_for_application_id :<> :*
+-- :doc Fetches tags assigned to given application
+SELECT t.tag_id, t.name
+FROM tags t JOIN application_tags at ON at.tag_id = t.tag_id
+WHERE at.application_id = $1 AND t.user_id = $2
+ORDER BY t.name
+
+-- :name upsert_tag :1
+-- :doc Creates a new tag for given user_id or returns the existing one
+INSERT INTO tags(tag_id, user_id, name) VALUES ($1, $2, $3)
+ON CONFLICT (user_id, name) DO UPDATE SET name=EXCLUDED.name
+RETURNING tag_id
+
+-- :name delete_tag
+-- :doc Deletes user's tag along with all its assignments
+DELETE FROM tags
+WHERE user_id = $1 AND name = $2
+
+-- :name tag_bookmark
+-- :doc Assigns a tag to bookmark
+INSERT OR IGNORE INTO bookmark_tags(bookmark_id, tag_id) VALUES ($1, $2)
+
+-- :name untag_bookmark
+-- :doc Removes a tag from bookmark
+DELETE FROM bookmark_tags
+WHERE bookmark_id = $1 AND tag_id IN (SELECT tag_id FROM tags WHERE user_id = $2 AND name = $3)
+
+-- :name tag_application
+-- :doc Assigns a tag to application
+INSERT OR IGNORE INTO application_tags(application_id, tag_id) VALUES ($1, $2)
+
+-- :name untag_application
+-- :doc Removes a tag from application
+DELETE FROM application_tags
+WHERE application_id = $1 AND tag_id IN (SELECT tag_id FROM tags WHERE user_id = $2 AND name = $3)
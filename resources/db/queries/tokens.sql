@@ -0,0 +1,28 @@
+-- :name fetch_tokens_for_user_id :<> :*
+-- :doc Fetches all API tokens of given user
+SELECT token_id, name, scope, created_at, last_used_at FROM api_tokens
+WHERE user_id = $1
+ORDER BY created_at, name
+
+-- :name create_token
+-- :doc Creates new API token of given user, storing only the hash of its secret
+INSERT INTO api_tokens(token_id, user_id, name, scope, token_hash) VALUES ($1, $2, $3, $4, $5)
+
+-- :name fetch_token_by_id :<> :?
+-- :doc Fetches user's API token by its identifier
+SELECT token_id, name, scope, created_at, last_used_at FROM api_tokens
+WHERE token_id = $1 AND user_id = $2
+
+-- :name delete_token
+-- :doc Revokes user's API token
+DELETE FROM api_tokens WHERE token_id = $1 AND user_id = $2
+
+-- :name fetch_user_by_token_hash :<> :?
+-- :doc Fetches owner of API token with given hash and scope
+SELECT u.user_id, u.email, u.name, u.picture, u.admin FROM api_tokens t
+JOIN users u ON u.user_id = t.user_id
+WHERE t.token_hash = $1 AND t.scope = $2
+
+-- :name update_token_usage
+-- :doc Records the time API token was used
+UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE token_hash = $1
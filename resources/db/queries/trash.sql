@@ -0,0 +1,102 @@
+-- :name fetch_trash_for_user_id :<> :*
+-- :doc Fetches user's trashed categories, bookmarks and applications, the recently trashed first
+SELECT 'category' AS kind, category_id AS id, name, NULL AS url, NULL AS category_id, deleted_at
+FROM categories
+WHERE user_id = $1 AND deleted_at IS NOT NULL
+UNION ALL
+SELECT 'bookmark' AS kind, bookmark_id AS id, name, url, category_id, deleted_at
+FROM bookmarks
+WHERE user_id = $1 AND deleted_at IS NOT NULL
+UNION ALL
+SELECT 'application' AS kind, application_id AS id, name, url, category_id, deleted_at
+FROM applications
+WHERE user_id = $1 AND deleted_at IS NOT NULL
+ORDER BY deleted_at DESC, kind, name
+
+-- :name restore_category_bookmarks
+-- :doc Restores bookmarks trashed along with user's category
+UPDATE bookmarks SET deleted_at=NULL
+WHERE category_id = $1 AND user_id = $2
+  AND deleted_at = (SELECT deleted_at FROM categories WHERE category_id = $1 AND user_id = $2)
+
+-- :name restore_category
+-- :doc Restores user's trashed category
+UPDATE categories SET deleted_at=NULL
+WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name restore_bookmark_category
+-- :doc Restores trashed category of user's trashed bookmark
+UPDATE categories SET deleted_at=NULL
+WHERE user_id = $2 AND deleted_at IS NOT NULL
+  AND category_id = (SELECT category_id FROM bookmarks
+                     WHERE bookmark_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL)
+
+-- :name restore_bookmark
+-- :doc Restores user's trashed bookmark
+UPDATE bookmarks SET deleted_at=NULL
+WHERE bookmark_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name restore_application
+-- :doc Restores user's trashed application
+UPDATE applications SET deleted_at=NULL
+WHERE application_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name purge_category_bookmarks
+-- :doc Permanently deletes bookmarks of user's trashed category
+DELETE FROM bookmarks
+WHERE user_id = $2
+  AND category_id IN (SELECT category_id FROM categories
+                      WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL)
+
+-- :name ungroup_category_applications
+-- :doc Takes applications out of user's trashed category
+UPDATE applications SET category_id=NULL
+WHERE user_id = $2
+  AND category_id IN (SELECT category_id FROM categories
+                      WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL)
+
+-- :name purge_category
+-- :doc Permanently deletes user's trashed category
+DELETE FROM categories
+WHERE category_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name purge_bookmark
+-- :doc Permanently deletes user's trashed bookmark
+DELETE FROM bookmarks
+WHERE bookmark_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name purge_application
+-- :doc Permanently deletes user's trashed application
+DELETE FROM applications
+WHERE application_id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+
+-- :name purge_bookmarks
+-- :doc Permanently deletes bookmarks trashed at least given number of days ago, or ones of
+-- such categories, optionally only the user's ones
+DELETE FROM bookmarks
+WHERE ($1 IS NULL OR user_id = $1)
+  AND (deleted_at <= datetime('now', '-' || $2 || ' days')
+       OR category_id IN (SELECT category_id FROM categories
+                          WHERE deleted_at <= datetime('now', '-' || $2 || ' days')))
+
+-- :name purge_applications
+-- :doc Permanently deletes applications trashed at least given number of days ago,
+-- optionally only the user's ones
+DELETE FROM applications
+WHERE ($1 IS NULL OR user_id = $1)
+  AND deleted_at <= datetime('now', '-' || $2 || ' days')
+
+-- :name ungroup_applications
+-- :doc Takes applications out of categories trashed at least given number of days ago,
+-- optionally only the user's ones
+UPDATE applications SET category_id=NULL
+WHERE ($1 IS NULL OR user_id = $1)
+  AND category_id IN (SELECT category_id FROM categories
+                      WHERE deleted_at <= datetime('now', '-' || $2 || ' days'))
+
+-- :name purge_categories
+-- :doc Permanently deletes categories trashed at least given number of days ago,
+-- optionally only the user's ones
+DELETE FROM categories
+WHERE ($1 IS NULL OR user_id = $1)
+  AND deleted_at <= datetime('now', '-' || $2 || ' days')
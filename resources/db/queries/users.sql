@@ -11,4 +11,12 @@ SET user_id=EXCLUDED.user_id, name=EXCLUDED.name, picture=EXCLUDED.picture
 
 -- :name fetch_user_by_id :<> :?
 -- :doc Fetches user by its identifier
-SELECT user_id, email, name, picture FROM users WHERE user_id = $1
+SELECT user_id, email, name, picture, admin FROM users WHERE user_id = $1
+
+-- :name fetch_user_by_email :<> :?
+-- :doc Fetches user by its email
+SELECT user_id, email, name, picture, admin FROM users WHERE email = $1
+
+-- :name update_user_admin
+-- :doc Grants or revokes admin rights of user with given email
+UPDATE users SET admin=$2 WHERE email = $1
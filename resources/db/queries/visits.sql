@@ -0,0 +1,14 @@
+-- :name fetch_item_url :<> :?
+-- :doc Fetches URL of user's bookmark or application, including the ones shared with user
+SELECT url FROM bookmarks WHERE bookmark_id = $1 AND user_id = $2 AND deleted_at IS NULL
+UNION ALL
+SELECT url FROM applications
+WHERE application_id = $1 AND deleted_at IS NULL
+  AND (user_id = $2
+       OR (shared AND (group_id IS NULL
+                       OR group_id IN (SELECT gm.group_id FROM group_members gm WHERE gm.user_id = $2))))
+LIMIT 1
+
+-- :name create_visit
+-- :doc Records user's visit of bookmark or application
+INSERT INTO visits(user_id, item_id) VALUES ($1, $2)
@@ -0,0 +1,653 @@
+//! Offline archives of bookmarked pages. Page gets captured as a single HTML document with
+//! stylesheets and images inlined (as data URLs) and scripts stripped, so that it might be
+//! viewed long after the original is gone. Archiving is opt-in - page is captured only when
+//! user asks for it - and each bookmark keeps its most recent snapshot only.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use futures::{stream, StreamExt};
+use lol_html::{element, html_content::ContentType, rewrite_str, text, RewriteStrSettings};
+use reqwest::Url;
+use scraper::{Html, Selector};
+use sqlx::SqlitePool;
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, ops::Range};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    errors::RequestError,
+    favicons, formats,
+    http::{self, Fetched, Outbound},
+    models::{
+        archive::{self, ArchiveInfo},
+        bookmark,
+        user::User,
+    },
+};
+
+const PAGE_SIZE_LIMIT: usize = 5 * 1024 * 1024;
+const RESOURCE_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Total size of resources inlined into a single page.
+const TOTAL_SIZE_LIMIT: usize = 20 * 1024 * 1024;
+const MAX_RESOURCES: usize = 200;
+
+/// Number of resources fetched at once.
+const CONCURRENCY: usize = 4;
+
+/// Elements dropped along with their content - scripts, embedded documents and elements
+/// which would make snapshot reach out for anything.
+const STRIPPED: &str = "script, noscript, iframe, frame, frameset, object, embed, applet, \
+                        base, source, meta[http-equiv], meta[charset]";
+
+/// Attributes which might hold `javascript:` URLs.
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "formaction", "xlink:href"];
+
+/// Position of the first `delimiter` which isn't escaped by backslash.
+fn unescaped_find(s: &str, delimiter: char) -> Option<usize> {
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == delimiter {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Resolves CSS escapes - backslash followed by hexadecimal code point or by escaped character.
+fn unescape_css(s: &str) -> Cow<'_, str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while let Some(digit) = chars.next_if(|c| c.is_ascii_hexdigit() && hex.len() < 6) {
+            hex.push(digit);
+        }
+        if hex.is_empty() {
+            // escaped newline is a line continuation
+            match chars.next() {
+                Some('\n') | None => {}
+                Some(escaped) => unescaped.push(escaped),
+            }
+        } else {
+            chars.next_if(|c| c.is_ascii_whitespace());
+            let code_point = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+            unescaped.push(
+                code_point
+                    .filter(|c| *c != '\0')
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            );
+        }
+    }
+    Cow::Owned(unescaped)
+}
+
+/// `url(...)` references in stylesheet, unescaped, along with their positions.
+fn css_urls(css: &str) -> Vec<(Range<usize>, Cow<'_, str>)> {
+    let mut urls = Vec::new();
+    let lowercase = css.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(found) = lowercase[offset..].find("url(") {
+        let start = offset + found;
+        let rest = &css[start + 4..];
+        let trimmed = rest.trim_start();
+        let skipped = rest.len() - trimmed.len();
+
+        let (url, after) = match trimmed.chars().next() {
+            Some(quote @ ('"' | '\'')) => match unescaped_find(&trimmed[1..], quote) {
+                Some(end) => (&trimmed[1..end + 1], &trimmed[end + 2..]),
+                None => break,
+            },
+            _ => match unescaped_find(trimmed, ')') {
+                Some(end) => (trimmed[..end].trim_end(), &trimmed[end..]),
+                None => break,
+            },
+        };
+        let Some(close) = after.find(')') else {
+            break;
+        };
+        let end = start + 4 + skipped + (trimmed.len() - after.len()) + close + 1;
+        urls.push((start..end, unescape_css(url)));
+        offset = end;
+    }
+    urls
+}
+
+fn is_inlined(reference: &str) -> bool {
+    let reference = reference.trim_start().to_ascii_lowercase();
+    reference.is_empty() || reference.starts_with("data:") || reference.starts_with('#')
+}
+
+/// Resources page is made of, fetched from their URLs.
+#[derive(Default)]
+struct Resources {
+    stylesheets: HashMap<Url, String>,
+    data_urls: HashMap<Url, String>,
+}
+
+impl Resources {
+    /// Replaces `url(...)` references of stylesheet by data URLs of fetched resources.
+    /// References to resources which couldn't be fetched are made absolute.
+    fn inline_css(&self, css: &str, base: &Url) -> String {
+        let mut inlined = String::with_capacity(css.len());
+        let mut last = 0;
+        for (range, reference) in css_urls(css) {
+            inlined.push_str(&css[last..range.start]);
+            match base.join(&reference) {
+                Ok(url) if !is_inlined(&reference) => {
+                    let target = self.data_urls.get(&url).cloned();
+                    inlined.push_str(&format!(
+                        "url(\"{}\")",
+                        target
+                            .unwrap_or_else(|| url.to_string())
+                            .replace('"', "%22")
+                    ));
+                }
+                _ => inlined.push_str(&css[range.clone()]),
+            }
+            last = range.end;
+        }
+        inlined.push_str(&css[last..]);
+        inlined
+    }
+
+    fn data_url(&self, base: &Url, reference: &str) -> Option<String> {
+        base.join(reference.trim())
+            .ok()
+            .and_then(|url| self.data_urls.get(&url).cloned())
+    }
+}
+
+/// Fetches resources of given URLs, skipping the ones which failed or didn't pass `accept`.
+/// Returns fetched resources along with their media types.
+async fn fetch_all(
+    outbound: &Outbound,
+    urls: Vec<Url>,
+    accept: fn(&Fetched) -> Option<String>,
+) -> Vec<(Url, String, Vec<u8>)> {
+    stream::iter(urls)
+        .map(|url| async move {
+            match outbound.fetch(url.clone(), RESOURCE_SIZE_LIMIT).await {
+                Ok(fetched) if fetched.status.is_success() => {
+                    let media_type = accept(&fetched)?;
+                    Some((url, media_type, fetched.body))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::debug!(error = ?e, %url, "Couldn't fetch archived resource");
+                    None
+                }
+            }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .filter_map(|resource| async move { resource })
+        .collect()
+        .await
+}
+
+fn stylesheet(fetched: &Fetched) -> Option<String> {
+    fetched
+        .media_type()
+        .is_none_or(|mt| mt == "text/css")
+        .then(|| "text/css".to_string())
+}
+
+/// Images and fonts referenced by stylesheets.
+fn any_resource(fetched: &Fetched) -> Option<String> {
+    favicons::image_type(fetched)
+        .or_else(|| fetched.media_type())
+        .filter(|mt| !mt.starts_with("text/"))
+}
+
+/// Collects URLs of resources referenced by HTML page - stylesheets, images and resources
+/// referenced by inline styles.
+fn page_resources(html: &str, base: &Url) -> (Vec<Url>, Vec<Url>) {
+    let document = Html::parse_document(html);
+    let links = Selector::parse("link[rel][href]").expect("Invalid selector");
+    let images = Selector::parse("img").expect("Invalid selector");
+    let styles = Selector::parse("style").expect("Invalid selector");
+    let styled = Selector::parse("[style]").expect("Invalid selector");
+
+    let mut stylesheets = Vec::new();
+    let mut resources = Vec::new();
+
+    for link in document.select(&links) {
+        let element = link.value();
+        let is_stylesheet = element.attr("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+        });
+        if let (true, Some(href)) = (is_stylesheet, element.attr("href")) {
+            stylesheets.extend(base.join(href.trim()).ok());
+        }
+    }
+    for image in document.select(&images) {
+        let element = image.value();
+        let src = element.attr("src").filter(|src| !src.trim().is_empty());
+        if let Some(src) = src.or(element.attr("data-src")) {
+            if !is_inlined(src) {
+                resources.extend(base.join(src.trim()).ok());
+            }
+        }
+    }
+    let css = document
+        .select(&styles)
+        .map(|style| style.text().collect::<String>())
+        .chain(
+            document
+                .select(&styled)
+                .filter_map(|e| e.value().attr("style").map(str::to_string)),
+        );
+    for css in css {
+        resources.extend(
+            css_urls(&css)
+                .into_iter()
+                .filter(|(_, reference)| !is_inlined(reference))
+                .filter_map(|(_, reference)| base.join(&reference).ok()),
+        );
+    }
+    (stylesheets, resources)
+}
+
+/// Fetches stylesheets and resources of page.
+async fn fetch_resources(
+    outbound: &Outbound,
+    stylesheets: Vec<Url>,
+    mut urls: Vec<Url>,
+) -> Resources {
+    let mut resources = Resources::default();
+    let mut size = 0;
+
+    for (url, _, css) in fetch_all(outbound, stylesheets, stylesheet).await {
+        size += css.len();
+        let css = String::from_utf8_lossy(&css).to_string();
+        urls.extend(
+            css_urls(&css)
+                .into_iter()
+                .filter(|(_, reference)| !is_inlined(reference))
+                .filter_map(|(_, reference)| url.join(&reference).ok()),
+        );
+        resources.stylesheets.insert(url, css);
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    urls.retain(|url| seen.insert(url.clone()));
+    urls.truncate(MAX_RESOURCES);
+
+    for (url, media_type, content) in fetch_all(outbound, urls, any_resource).await {
+        if size + content.len() > TOTAL_SIZE_LIMIT {
+            continue;
+        }
+        size += content.len();
+        resources.data_urls.insert(
+            url,
+            format!("data:{media_type};base64,{}", BASE64.encode(content)),
+        );
+    }
+
+    // stylesheets might only be inlined once their own resources are known
+    let stylesheets = std::mem::take(&mut resources.stylesheets);
+    resources.stylesheets = stylesheets
+        .into_iter()
+        .map(|(url, css)| {
+            let css = resources.inline_css(&css, &url);
+            (url, css)
+        })
+        .collect();
+    resources
+}
+
+/// Rewrites page into a self-contained one, with scripts stripped and resources inlined.
+fn rewrite_page(
+    html: &str,
+    page_url: &Url,
+    base: &Url,
+    resources: &Resources,
+    captured_at: OffsetDateTime,
+) -> anyhow::Result<String> {
+    let style = RefCell::new(String::new());
+    let banner = format!(
+        "<meta charset=\"utf-8\"><!-- Archived by trufel from {} at {} -->",
+        formats::escape(page_url.as_str()).replace("--", "&#45;&#45;"),
+        captured_at.format(&Rfc3339)?
+    );
+
+    let rewritten = rewrite_str(
+        html,
+        RewriteStrSettings {
+            element_content_handlers: vec![
+                element!(STRIPPED, |el| {
+                    el.remove();
+                    Ok(())
+                }),
+                element!("head", |el| {
+                    el.prepend(&banner, ContentType::Html);
+                    Ok(())
+                }),
+                element!("link", |el| {
+                    let css = el
+                        .get_attribute("href")
+                        .and_then(|href| base.join(href.trim()).ok())
+                        .and_then(|url| resources.stylesheets.get(&url));
+                    let is_stylesheet = el.get_attribute("rel").is_some_and(|rel| {
+                        rel.split_whitespace()
+                            .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+                    });
+                    match (is_stylesheet, css) {
+                        (true, Some(css)) => {
+                            let media = el
+                                .get_attribute("media")
+                                .map(|m| format!(" media=\"{}\"", formats::escape(&m)))
+                                .unwrap_or_default();
+                            el.replace(
+                                &format!("<style{media}>{}</style>", css.replace("</", "<\\/")),
+                                ContentType::Html,
+                            );
+                        }
+                        _ => el.remove(),
+                    }
+                    Ok(())
+                }),
+                element!("img", |el| {
+                    let src = el.get_attribute("src").filter(|src| !src.trim().is_empty());
+                    if let Some(src) = src.or(el.get_attribute("data-src")) {
+                        if !is_inlined(&src) {
+                            let target = resources
+                                .data_url(base, &src)
+                                .or_else(|| base.join(src.trim()).ok().map(|url| url.to_string()));
+                            el.set_attribute("src", &target.unwrap_or_default())?;
+                        }
+                    }
+                    for attribute in ["srcset", "sizes", "data-src", "loading"] {
+                        el.remove_attribute(attribute);
+                    }
+                    Ok(())
+                }),
+                element!("a[href], area[href]", |el| {
+                    if let Some(href) = el.get_attribute("href") {
+                        if let Ok(url) = base.join(href.trim()) {
+                            el.set_attribute("href", url.as_str())?;
+                        }
+                    }
+                    Ok(())
+                }),
+                element!("*", |el| {
+                    let names: Vec<String> = el.attributes().iter().map(|a| a.name()).collect();
+                    for name in names {
+                        let javascript = URL_ATTRIBUTES.contains(&name.as_str())
+                            && el.get_attribute(&name).is_some_and(|value| {
+                                value
+                                    .trim_start()
+                                    .to_ascii_lowercase()
+                                    .starts_with("javascript:")
+                            });
+                        if name.starts_with("on") || javascript {
+                            el.remove_attribute(&name);
+                        }
+                    }
+                    if let Some(css) = el.get_attribute("style") {
+                        el.set_attribute("style", &resources.inline_css(&css, base))?;
+                    }
+                    Ok(())
+                }),
+                text!("style", |chunk| {
+                    style.borrow_mut().push_str(chunk.as_str());
+                    if chunk.last_in_text_node() {
+                        let css = resources.inline_css(&style.take(), base);
+                        chunk.replace(&css.replace("</", "<\\/"), ContentType::Html);
+                    } else {
+                        chunk.remove();
+                    }
+                    Ok(())
+                }),
+            ],
+            ..RewriteStrSettings::new()
+        },
+    )?;
+    Ok(rewritten)
+}
+
+/// Captures page of given URL as a single, self-contained HTML document.
+pub async fn capture(outbound: &Outbound, url: &str) -> anyhow::Result<String> {
+    let url = Url::parse(url)?;
+    let page = outbound
+        .fetch(url.clone(), PAGE_SIZE_LIMIT)
+        .await
+        .map_err(|e| RequestError::Invalid("url", format!("{url} ({e})")))?;
+
+    if !page.status.is_success() {
+        return Err(RequestError::Invalid(
+            "url",
+            format!("{url} (responded with {})", page.status),
+        )
+        .into());
+    }
+    if !page
+        .media_type()
+        .is_none_or(|mt| mt == "text/html" || mt == "application/xhtml+xml")
+    {
+        return Err(RequestError::Invalid("url", format!("{url} (not an HTML page)")).into());
+    }
+
+    let html = String::from_utf8_lossy(&page.body);
+    let base = {
+        let document = Html::parse_document(&html);
+        let base_href = Selector::parse("base[href]").expect("Invalid selector");
+        document
+            .select(&base_href)
+            .next()
+            .and_then(|base| page.url.join(base.value().attr("href")?).ok())
+            .unwrap_or_else(|| page.url.clone())
+    };
+    let (stylesheets, urls) = page_resources(&html, &base);
+    let resources = fetch_resources(outbound, stylesheets, urls).await;
+
+    rewrite_page(
+        &html,
+        &page.url,
+        &base,
+        &resources,
+        OffsetDateTime::now_utc(),
+    )
+}
+
+/// Captures snapshot of bookmarked page, replacing the previous one.
+pub async fn archive_bookmark(
+    pool: &SqlitePool,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<ArchiveInfo> {
+    let bookmark = bookmark::find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+
+    tracing::info!(url = bookmark.url, "Archiving bookmarked page");
+    let html = capture(http::outbound(), &bookmark.url).await?;
+    archive::store_archive(pool, bookmark_id, &bookmark.url, &html).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http::header, routing::get, Router};
+
+    use super::*;
+    use crate::http::{AddressPolicy, OutboundConfig};
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    fn references(css: &str) -> Vec<(&str, String)> {
+        css_urls(css)
+            .into_iter()
+            .map(|(range, url)| (&css[range], url.into_owned()))
+            .collect()
+    }
+
+    fn png(size: usize) -> ([(header::HeaderName, &'static str); 1], Vec<u8>) {
+        let mut body = PNG.to_vec();
+        body.resize(size, 0);
+        ([(header::CONTENT_TYPE, "image/png")], body)
+    }
+
+    /// Serves images of various sizes on a local port, returning server's base URL.
+    async fn serve() -> Url {
+        let app = Router::new()
+            .route("/small/:n", get(|| async { png(64) }))
+            .route("/large/:n", get(|| async { png(RESOURCE_SIZE_LIMIT - 1) }))
+            .route("/huge", get(|| async { png(RESOURCE_SIZE_LIMIT + 1) }));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        Url::parse(&format!("http://{addr}/")).unwrap()
+    }
+
+    fn outbound() -> Outbound {
+        Outbound::new(OutboundConfig {
+            policy: AddressPolicy::default().with("127.0.0.1", "").unwrap(),
+            ..Default::default()
+        })
+    }
+
+    fn rewrite(html: &str) -> String {
+        let url = Url::parse("https://example.com/page/").unwrap();
+        rewrite_page(
+            html,
+            &url,
+            &url,
+            &Resources::default(),
+            OffsetDateTime::UNIX_EPOCH,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_quoted_and_unquoted_urls() {
+        assert_eq!(
+            references(
+                r#"a { background: URL( "a.png" ) } b { src: url('b c.woff') url(  c.png  ) }"#
+            ),
+            vec![
+                (r#"URL( "a.png" )"#, "a.png".to_string()),
+                ("url('b c.woff')", "b c.woff".to_string()),
+                ("url(  c.png  )", "c.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_urls() {
+        assert_eq!(
+            references(r#"a { background: url("a\"b.png") url('c\'d.png') url(e\)f.png) }"#),
+            vec![
+                (r#"url("a\"b.png")"#, r#"a"b.png"#.to_string()),
+                (r#"url('c\'d.png')"#, "c'd.png".to_string()),
+                (r#"url(e\)f.png)"#, "e)f.png".to_string()),
+            ]
+        );
+        assert_eq!(
+            references(r#"url("\66 oo\2e png")"#),
+            vec![(r#"url("\66 oo\2e png")"#, "foo.png".to_string())]
+        );
+    }
+
+    #[test]
+    fn skips_unterminated_urls() {
+        assert_eq!(
+            references("a { background: url(a.png) } b { background: url(\"b.png }"),
+            vec![("url(a.png)", "a.png".to_string())]
+        );
+    }
+
+    #[test]
+    fn keeps_data_urls_as_they_are() {
+        let css = r#"a { background: url("data:image/png;base64,AAAA") url(#mask) url(b.png) }"#;
+        let urls = css_urls(css);
+        assert_eq!(urls.len(), 3);
+        assert!(is_inlined(&urls[0].1));
+        assert!(is_inlined(&urls[1].1));
+        assert!(!is_inlined(&urls[2].1));
+
+        let base = Url::parse("https://example.com/css/").unwrap();
+        let mut resources = Resources::default();
+        resources.data_urls.insert(
+            base.join("b.png").unwrap(),
+            "data:image/png;base64,BBBB".to_string(),
+        );
+        assert_eq!(
+            resources.inline_css(css, &base),
+            r#"a { background: url("data:image/png;base64,AAAA") url(#mask) url("data:image/png;base64,BBBB") }"#
+        );
+    }
+
+    #[test]
+    fn strips_scripts_and_embedded_documents() {
+        let html = rewrite(
+            r#"<html><head><script src="a.js"></script><base href="/"></head>
+            <body><p>Kept</p><script>alert(1)</script><noscript>No script</noscript>
+            <iframe src="https://example.org/"></iframe><object data="a.swf"></object></body></html>"#,
+        );
+
+        assert!(html.contains("<p>Kept</p>"));
+        for stripped in [
+            "<script",
+            "alert",
+            "<noscript",
+            "<iframe",
+            "<object",
+            "<base",
+        ] {
+            assert!(!html.contains(stripped), "{stripped} should be stripped");
+        }
+    }
+
+    #[test]
+    fn strips_event_handlers_and_javascript_urls() {
+        let html = rewrite(
+            r#"<body onload="steal()"><a href=" JavaScript:steal()" onclick="steal()">A</a>
+            <a href="other">B</a><form action="javascript:steal()"><button formaction="javascript:steal()">C</button></form>
+            <svg><a xlink:href="javascript:steal()">D</a></svg><img src="x.png" onerror="steal()"></body>"#,
+        );
+
+        assert!(!html.contains("steal"));
+        assert!(html.contains(r#"<a href="https://example.com/page/other">B</a>"#));
+        assert!(html.contains(r#"src="https://example.com/page/x.png""#));
+    }
+
+    #[tokio::test]
+    async fn limits_number_of_resources() {
+        let base = serve().await;
+        let urls = (0..MAX_RESOURCES + 10)
+            .map(|n| base.join(&format!("small/{n}")).unwrap())
+            .collect();
+
+        let resources = fetch_resources(&outbound(), vec![], urls).await;
+        assert_eq!(resources.data_urls.len(), MAX_RESOURCES);
+    }
+
+    #[tokio::test]
+    async fn limits_size_of_resources() {
+        let base = serve().await;
+        let urls = (0..TOTAL_SIZE_LIMIT / RESOURCE_SIZE_LIMIT + 1)
+            .map(|n| base.join(&format!("large/{n}")).unwrap())
+            .chain([base.join("huge").unwrap()])
+            .collect();
+
+        let resources = fetch_resources(&outbound(), vec![], urls).await;
+        assert_eq!(
+            resources.data_urls.len(),
+            TOTAL_SIZE_LIMIT / RESOURCE_SIZE_LIMIT
+        );
+        assert!(!resources
+            .data_urls
+            .contains_key(&base.join("huge").unwrap()));
+    }
+}
@@ -0,0 +1,417 @@
+//! Full-fidelity backup of user's dashboard - categories, applications and bookmarks along
+//! with all their properties and tags, as a single JSON document.
+//!
+//! Backup document is versioned with the version of DB schema it's been made with (the app
+//! semver of the most recently applied migration). Backups made by older versions can be
+//! restored by newer ones, never the other way round.
+
+use hugsqlx::{params, HugSqlx};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    db,
+    errors::{InternalError, RequestError},
+    importer::{ImportReport, ImportedEntry, SkippedEntry},
+    models::{
+        application::{self, Application},
+        bookmark::{self, Bookmark},
+        category::{self, Category},
+        tag,
+        user::User,
+    },
+    notes::Notes,
+    urls,
+};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/backup.sql"]
+struct Backups {}
+
+#[derive(sqlx::FromRow)]
+struct ItemTag {
+    item_id: Uuid,
+    name: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemUrl {
+    url: String,
+}
+
+/// Application or bookmark along with names of its tags.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackupItem<T> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Backup {
+    pub version: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    #[serde(default)]
+    pub categories: Vec<Category>,
+    #[serde(default)]
+    pub applications: Vec<BackupItem<Application>>,
+    #[serde(default)]
+    pub bookmarks: Vec<BackupItem<Bookmark>>,
+}
+
+/// How backup gets restored.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// Backup is added to user's dashboard. Categories are matched by names, applications
+    /// and bookmarks already existing (by their URLs) are reported as duplicates.
+    #[default]
+    Merge,
+
+    /// User's dashboard is wiped out and replaced by backup, keeping backup's order.
+    Replace,
+}
+
+fn tags_by_item(tags: Vec<ItemTag>) -> HashMap<Uuid, Vec<String>> {
+    let mut by_item: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for tag in tags {
+        by_item.entry(tag.item_id).or_default().push(tag.name);
+    }
+    by_item
+}
+
+/// Collects all user's categories, applications and bookmarks into a backup document.
+pub async fn export_backup(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Backup> {
+    let version = db::schema_version(pool).await?;
+    let categories = category::fetch_categories(pool, user).await?;
+    let applications = application::fetch_own_applications(pool, user).await?;
+    let bookmarks = bookmark::fetch_bookmarks(pool, user, None).await?;
+
+    let mut application_tags = tags_by_item(
+        Backups::fetch_application_tags_for_user_id::<_, ItemTag>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load applications' tags");
+                InternalError::TagsFetch
+            })?,
+    );
+    let mut bookmark_tags = tags_by_item(
+        Backups::fetch_bookmark_tags_for_user_id::<_, ItemTag>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load bookmarks' tags");
+                InternalError::TagsFetch
+            })?,
+    );
+
+    Ok(Backup {
+        version: version.to_string(),
+        exported_at: OffsetDateTime::now_utc(),
+        categories,
+        applications: applications
+            .into_iter()
+            .map(|item| BackupItem {
+                tags: application_tags.remove(&item.id).unwrap_or_default(),
+                item,
+            })
+            .collect(),
+        bookmarks: bookmarks
+            .into_iter()
+            .map(|item| BackupItem {
+                tags: bookmark_tags.remove(&item.id).unwrap_or_default(),
+                item,
+            })
+            .collect(),
+    })
+}
+
+/// Restores backup document in a single transaction - either everything gets restored
+/// or nothing. Restored items get new identifiers, so that the same backup might be
+/// restored by many users.
+pub async fn restore_backup(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    backup: Backup,
+    mode: RestoreMode,
+) -> anyhow::Result<ImportReport> {
+    let schema_version = db::schema_version(pool).await?;
+    let version = Version::parse(&backup.version).map_err(|e| {
+        RequestError::Invalid("backup version", format!("{} ({e})", backup.version))
+    })?;
+
+    if version > schema_version {
+        return Err(RequestError::Invalid(
+            "backup version",
+            format!("{version} (backups up to version {schema_version} are supported)"),
+        )
+        .into());
+    }
+
+    let mut tx = pool.begin().await.map_err(restore_error)?;
+    let report = restore(&mut tx, user, backup, mode).await?;
+    tx.commit().await.map_err(restore_error)?;
+
+    Ok(report)
+}
+
+fn restore_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't restore backup");
+    InternalError::BackupRestore
+}
+
+/// Restores backed up categories, applications and bookmarks (in this order) within
+/// a transaction of given connection.
+async fn restore(
+    conn: &mut SqliteConnection,
+    user: &User,
+    mut backup: Backup,
+    mode: RestoreMode,
+) -> anyhow::Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut bookmark_urls: HashSet<String> = HashSet::new();
+    let mut application_urls: HashSet<String> = HashSet::new();
+
+    match mode {
+        RestoreMode::Replace => {
+            Backups::delete_bookmarks_for_user_id(&mut *conn, params!(user.id))
+                .await
+                .map_err(restore_error)?;
+            Backups::delete_applications_for_user_id(&mut *conn, params!(user.id))
+                .await
+                .map_err(restore_error)?;
+            Backups::delete_categories_for_user_id(&mut *conn, params!(user.id))
+                .await
+                .map_err(restore_error)?;
+            Backups::delete_tags_for_user_id(&mut *conn, params!(user.id))
+                .await
+                .map_err(restore_error)?;
+        }
+        RestoreMode::Merge => {
+            bookmark_urls.extend(
+                Backups::fetch_bookmark_urls_for_user_id::<_, ItemUrl>(
+                    &mut *conn,
+                    params!(user.id),
+                )
+                .await
+                .map_err(restore_error)?
+                .into_iter()
+                .map(|u| u.url),
+            );
+            application_urls.extend(
+                Backups::fetch_application_urls_for_user_id::<_, ItemUrl>(
+                    &mut *conn,
+                    params!(user.id),
+                )
+                .await
+                .map_err(restore_error)?
+                .into_iter()
+                .map(|u| u.url),
+            );
+        }
+    }
+
+    // merged items go to the end, replaced ones keep their positions
+    let keep_position = |position: u16| (mode == RestoreMode::Replace).then_some(position);
+
+    // backed up category ids along with ids and names of restored categories
+    let mut categories: HashMap<Uuid, (Uuid, String)> = HashMap::new();
+    let mut tags: HashMap<String, Uuid> = HashMap::new();
+
+    backup.categories.sort_by_key(|c| c.position);
+    for c in backup.categories {
+        let existing = match mode {
+            RestoreMode::Merge => Backups::fetch_category_by_name::<_, Category>(
+                &mut *conn,
+                params!(&c.name, user.id),
+            )
+            .await
+            .map_err(restore_error)?,
+            RestoreMode::Replace => None,
+        };
+        let id = match existing {
+            Some(existing) => existing.id,
+            None => {
+                let id = Uuid::new_v4();
+                let position = (mode == RestoreMode::Replace).then_some(c.position);
+                Backups::restore_category(
+                    &mut *conn,
+                    params!(id, user.id, &c.name, position, c.nested),
+                )
+                .await
+                .map_err(restore_error)?;
+                report.categories_created.push(c.name.clone());
+                id
+            }
+        };
+        categories.insert(c.id, (id, c.name));
+    }
+
+    backup.applications.sort_by_key(|a| a.item.position);
+    for BackupItem {
+        item: a,
+        tags: names,
+    } in backup.applications
+    {
+        let category = a
+            .category_id
+            .and_then(|id| categories.get(&id))
+            .map_or("", |(_, name)| name.as_str());
+
+        let url = match urls::validate_url(&a.url) {
+            Ok(url) => url.to_string(),
+            Err(e) => {
+                report.skipped.push(SkippedEntry {
+                    category: category.to_string(),
+                    name: a.name,
+                    url: a.url,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+        if !application_urls.insert(url.clone()) && mode == RestoreMode::Merge {
+            report.duplicates.push(ImportedEntry {
+                category: category.to_string(),
+                name: a.name,
+                url: a.url,
+            });
+            continue;
+        }
+        let id = Uuid::new_v4();
+        let category_id = a
+            .category_id
+            .and_then(|id| categories.get(&id))
+            .map(|(id, _)| *id);
+
+        Backups::restore_application(
+            &mut *conn,
+            params!(
+                id,
+                user.id,
+                category_id,
+                a.name,
+                a.description,
+                url,
+                a.icon,
+                a.visible,
+                a.shared && user.admin,
+                a.searchable && a.search_url.is_some(),
+                a.created_at,
+                keep_position(a.position),
+                a.notes.map(Notes::into_markdown),
+                a.group_id,
+                a.search_url,
+                a.keyword
+            ),
+        )
+        .await
+        .map_err(restore_error)?;
+
+        for tag_id in restore_tags(conn, user, &mut tags, names).await? {
+            Backups::restore_application_tag(&mut *conn, params!(id, tag_id))
+                .await
+                .map_err(restore_error)?;
+        }
+        report.applications_imported += 1;
+    }
+
+    backup.bookmarks.sort_by_key(|b| b.item.position);
+    for BackupItem {
+        item: b,
+        tags: names,
+    } in backup.bookmarks
+    {
+        let Some((category_id, category)) = categories.get(&b.category_id).cloned() else {
+            report.skipped.push(SkippedEntry {
+                category: String::new(),
+                name: b.name,
+                url: b.url,
+                reason: RequestError::NotFound("Category").to_string(),
+            });
+            continue;
+        };
+        let url = match urls::validate_url(&b.url) {
+            Ok(url) => url.to_string(),
+            Err(e) => {
+                report.skipped.push(SkippedEntry {
+                    category,
+                    name: b.name,
+                    url: b.url,
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+        if !bookmark_urls.insert(url.clone()) && mode == RestoreMode::Merge {
+            report.duplicates.push(ImportedEntry {
+                category,
+                name: b.name,
+                url: b.url,
+            });
+            continue;
+        }
+        let id = Uuid::new_v4();
+        Backups::restore_bookmark(
+            &mut *conn,
+            params!(
+                id,
+                user.id,
+                category_id,
+                b.name,
+                url,
+                b.icon,
+                b.visible,
+                b.created_at,
+                keep_position(b.position),
+                b.notes.map(Notes::into_markdown)
+            ),
+        )
+        .await
+        .map_err(restore_error)?;
+
+        for tag_id in restore_tags(conn, user, &mut tags, names).await? {
+            Backups::restore_bookmark_tag(&mut *conn, params!(id, tag_id))
+                .await
+                .map_err(restore_error)?;
+        }
+        report.imported += 1;
+    }
+    Ok(report)
+}
+
+/// Returns identifiers of tags with given names, creating the missing ones.
+/// Invalid tag names are ignored.
+async fn restore_tags(
+    conn: &mut SqliteConnection,
+    user: &User,
+    tags: &mut HashMap<String, Uuid>,
+    names: Vec<String>,
+) -> anyhow::Result<Vec<Uuid>> {
+    let mut ids = Vec::with_capacity(names.len());
+    for name in names {
+        let Ok(name) = tag::normalize_tag(&name) else {
+            continue;
+        };
+        let id = match tags.get(&name) {
+            Some(id) => *id,
+            None => {
+                let id: Uuid =
+                    Backups::restore_tag(&mut *conn, params!(Uuid::new_v4(), user.id, &name))
+                        .await
+                        .map(|row| row.get(0))
+                        .map_err(restore_error)?;
+                tags.insert(name, id);
+                id
+            }
+        };
+        ids.push(id);
+    }
+    Ok(ids)
+}
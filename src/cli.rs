@@ -0,0 +1,100 @@
+use anyhow::{anyhow, bail};
+use sqlx::SqlitePool;
+use std::path::Path;
+
+use crate::{
+    formats::{csv, dashy, flame, heimdall, homer, netscape, xbel},
+    importer::{self, ImportReport},
+    models::user::{self, User},
+};
+
+const USAGE: &str = "\
+Usage: trufel [COMMAND]
+
+Runs the server when no command is given.
+
+Commands:
+  import-netscape <email> <file>    Imports bookmarks from Netscape bookmark file
+  import-xbel <email> <file>        Imports bookmarks from XBEL document
+  import-csv <email> <file>         Imports bookmarks from CSV spreadsheet (category,name,url,...)
+  import-flame <email> <file>       Imports Flame dashboard from its SQLite database or JSON
+  import-homer <email> <file>       Imports applications from Homer's config.yml
+  import-dashy <email> <file>       Imports applications from Dashy's conf.yml
+  import-heimdall <email> <file>    Imports applications from Heimdall's JSON export
+  grant-admin <email>               Lets user manage applications shared with others
+  revoke-admin <email>              Takes admin rights away from user";
+
+/// Runs command given in command line arguments. Returns `false` if there was no command
+/// to run, so the server should be started instead.
+pub async fn run(pool: &SqlitePool, args: &[String]) -> anyhow::Result<bool> {
+    match args {
+        [] => Ok(false),
+        [cmd, email, file] if cmd == "import-netscape" => {
+            let user = find_user(pool, email).await?;
+            let html = std::fs::read_to_string(file)?;
+            let report = importer::import_folders(pool, &user, netscape::parse(&html)).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-xbel" => {
+            let user = find_user(pool, email).await?;
+            let folders = xbel::parse(&std::fs::read_to_string(file)?)?;
+            let report = importer::import_folders(pool, &user, folders).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-csv" => {
+            let user = find_user(pool, email).await?;
+            let folders = csv::parse(&std::fs::read_to_string(file)?, &Default::default())?;
+            let report = importer::import_folders(pool, &user, folders).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-flame" => {
+            let user = find_user(pool, email).await?;
+            let content = std::fs::read(file)?;
+            let dashboard = if flame::is_sqlite(&content) {
+                flame::read_sqlite(Path::new(file)).await?
+            } else {
+                flame::parse_json(std::str::from_utf8(&content)?)?
+            };
+            let report = importer::import_dashboard(pool, &user, dashboard).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-homer" => {
+            let user = find_user(pool, email).await?;
+            let dashboard = homer::parse(&std::fs::read_to_string(file)?)?;
+            let report = importer::import_dashboard(pool, &user, dashboard).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-dashy" => {
+            let user = find_user(pool, email).await?;
+            let dashboard = dashy::parse(&std::fs::read_to_string(file)?)?;
+            let report = importer::import_dashboard(pool, &user, dashboard).await?;
+            print_report(&report)
+        }
+        [cmd, email, file] if cmd == "import-heimdall" => {
+            let user = find_user(pool, email).await?;
+            let dashboard = heimdall::parse(&std::fs::read_to_string(file)?)?;
+            let report = importer::import_dashboard(pool, &user, dashboard).await?;
+            print_report(&report)
+        }
+        [cmd, email] if cmd == "grant-admin" => {
+            user::set_admin(pool, email, true).await?;
+            Ok(true)
+        }
+        [cmd, email] if cmd == "revoke-admin" => {
+            user::set_admin(pool, email, false).await?;
+            Ok(true)
+        }
+        _ => bail!(USAGE),
+    }
+}
+
+async fn find_user(pool: &SqlitePool, email: &str) -> anyhow::Result<User> {
+    user::find_by_email(pool, email)
+        .await?
+        .ok_or_else(|| anyhow!("No user found with email {email}"))
+}
+
+fn print_report(report: &ImportReport) -> anyhow::Result<bool> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(true)
+}
@@ -78,17 +78,26 @@ async fn upgrade(
     base_version: String,
     app_semver: Version,
 ) -> anyhow::Result<Version> {
-    if let Some(migration) = build_migration(base_version, &app_semver) {
-        match pool.execute(migration.as_str()).await {
-            Ok(_) => {
-                let (m, current_version) = version(pool).await?;
-                tracing::debug!("Upgraded to latest migration {m}");
-                return Ok(current_version);
-            }
-            Err(e) => panic!("Couldn't upgrade the schema. Bailing out: {e}")
+    let migrations = build_migration(base_version, &app_semver);
+    if migrations.is_empty() {
+        return Ok(app_semver);
+    }
+
+    // every migration gets committed on its own, as SQLite can't drop virtual tables
+    // created within the same transaction
+    for migration in migrations {
+        if let Err(e) = pool.execute(migration.as_str()).await {
+            panic!("Couldn't upgrade the schema. Bailing out: {e}")
         }
     }
-    Ok(app_semver)
+    let (m, current_version) = version(pool).await?;
+    tracing::debug!("Upgraded to latest migration {m}");
+    Ok(current_version)
+}
+
+/// Returns app semver of the most recently applied migration, which is a version of DB schema.
+pub async fn schema_version(pool: &SqlitePool) -> anyhow::Result<Version> {
+    Ok(version(pool).await?.1)
 }
 
 async fn version(pool: &SqlitePool) -> anyhow::Result<(String, Version)> {
@@ -104,7 +113,7 @@ async fn version(pool: &SqlitePool) -> anyhow::Result<(String, Version)> {
         .map_err(Into::into)
 }
 
-fn build_migration(base_version: String, app_semver: &Version) -> Option<String> {
+fn build_migration(base_version: String, app_semver: &Version) -> Vec<String> {
     let mut migrations: Vec<Migration> = Asset::iter()
         .filter_map(|res| {
             let v: Vec<&str> = res.split("__").collect();
@@ -126,32 +135,24 @@ fn build_migration(base_version: String, app_semver: &Version) -> Option<String>
     // keep only those which haven't been applied yet
     migrations.retain(|m| base_version.is_empty() || m.version.gt(&base_version));
 
-    // ...and compose final transaction
-    let final_txn = migrations.iter().fold(String::default(), |mut txn, m| {
-        let buf = Asset::get(m.file.as_ref()).unwrap();
-        match str::from_utf8(buf.data.as_ref()) {
-            Ok(s) => {
-                txn.push_str(s);
-                txn.push_str(
-                    format!(
-                        "\n\n\
-                         INSERT INTO migrations(version, description, script, app_semver) \
-                         VALUES ('{version}', '{description}', '{script}', '{semver}');\n\n",
-                        version = m.version,
-                        description = m.description,
-                        script = m.file,
-                        semver = app_semver
-                    )
-                    .as_str(),
-                );
-                txn
+    // ...and compose a transaction of each
+    migrations
+        .iter()
+        .map(|m| {
+            let buf = Asset::get(m.file.as_ref()).unwrap();
+            match str::from_utf8(buf.data.as_ref()) {
+                Ok(s) => format!(
+                    "BEGIN TRANSACTION;\n\n{s}\n\n\
+                     INSERT INTO migrations(version, description, script, app_semver) \
+                     VALUES ('{version}', '{description}', '{script}', '{semver}');\n\n\
+                     COMMIT;",
+                    version = m.version,
+                    description = m.description,
+                    script = m.file,
+                    semver = app_semver
+                ),
+                _ => panic!("Non UTF8 format of migration file!"),
             }
-            _ => panic!("Non UTF8 format of migration file!"),
-        }
-    });
-    if final_txn.is_empty() {
-        None
-    } else {
-        Some(format!("BEGIN TRANSACTION;\n\n{}\n\nCOMMIT;", final_txn))
-    }
+        })
+        .collect()
 }
@@ -27,6 +27,99 @@ pub enum InternalError {
 
     #[error("New category couldn't be created")]
     CategoriesCreate,
+
+    #[error("Category couldn't be deleted")]
+    CategoriesDelete,
+
+    #[error("New bookmark couldn't be created")]
+    BookmarksCreate,
+
+    #[error("Bookmark couldn't be updated")]
+    BookmarksUpdate,
+
+    #[error("Bookmark couldn't be deleted")]
+    BookmarksDelete,
+
+    #[error("New application couldn't be created")]
+    AppsCreate,
+
+    #[error("Application couldn't be updated")]
+    AppsUpdate,
+
+    #[error("Application couldn't be deleted")]
+    AppsDelete,
+
+    #[error("User's tags couldn't be fetched")]
+    TagsFetch,
+
+    #[error("Tags couldn't be updated")]
+    TagsUpdate,
+
+    #[error("Backup couldn't be restored")]
+    BackupRestore,
+
+    #[error("Link checks couldn't be fetched")]
+    LinkChecksFetch,
+
+    #[error("Link check couldn't be stored")]
+    LinkChecksUpdate,
+
+    #[error("Icon couldn't be fetched")]
+    IconsFetch,
+
+    #[error("Icon couldn't be stored")]
+    IconsUpdate,
+
+    #[error("Search couldn't be performed")]
+    Search,
+
+    #[error("Visit couldn't be recorded")]
+    VisitsCreate,
+
+    #[error("Archived page couldn't be fetched")]
+    ArchivesFetch,
+
+    #[error("Archived page couldn't be stored")]
+    ArchivesUpdate,
+
+    #[error("Duplicates couldn't be merged")]
+    DuplicatesMerge,
+
+    #[error("Bulk operation couldn't be performed")]
+    BulkUpdate,
+
+    #[error("Trash couldn't be fetched")]
+    TrashFetch,
+
+    #[error("Trash couldn't be updated")]
+    TrashUpdate,
+
+    #[error("API tokens couldn't be fetched")]
+    TokensFetch,
+
+    #[error("API token couldn't be updated")]
+    TokensUpdate,
+
+    #[error("Groups couldn't be fetched")]
+    GroupsFetch,
+
+    #[error("Group couldn't be updated")]
+    GroupsUpdate,
+}
+
+#[derive(Error, Debug)]
+pub enum RequestError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+
+    #[error("Invalid {0}: {1}")]
+    Invalid(&'static str, String),
+
+    #[error("Duplicate {0}: {1}")]
+    Conflict(&'static str, String),
+
+    #[error("Only admins can {0}")]
+    AdminOnly(&'static str),
 }
 
 #[derive(Error, Debug)]
@@ -37,6 +130,9 @@ pub enum AuthError {
     #[error("Invalid claims")]
     InvalidClaims,
 
+    #[error("Invalid API token")]
+    InvalidApiToken,
+
     #[error("JWT validation error")]
     JWTValidationError(ValidationError),
 
@@ -49,6 +145,9 @@ pub enum AuthError {
 
 impl IntoResponse for ServiceError {
     fn into_response(self) -> Response<Body> {
+        if let Some(e) = self.0.downcast_ref::<RequestError>() {
+            return e.to_response();
+        }
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Something went wrong: {}", self.0),
@@ -57,6 +156,21 @@ impl IntoResponse for ServiceError {
     }
 }
 
+impl RequestError {
+    fn to_response(&self) -> Response<Body> {
+        let status = match self {
+            RequestError::NotFound(_) => StatusCode::NOT_FOUND,
+            RequestError::Invalid(..) => StatusCode::UNPROCESSABLE_ENTITY,
+            RequestError::Conflict(..) => StatusCode::CONFLICT,
+            RequestError::AdminOnly(_) => StatusCode::FORBIDDEN,
+        };
+        let body = Json(json!({
+            "error": self.to_string(),
+        }));
+        (status, body).into_response()
+    }
+}
+
 /// This enables using `?` on functions that return `Result<_, anyhow::Error>` to turn them into
 /// `Result<_, ServiceError>`. That way we don't need to do that manually.
 impl<E> From<E> for ServiceError
@@ -80,6 +194,7 @@ impl IntoResponse for AuthError {
                 (StatusCode::SERVICE_UNAVAILABLE, "Cannot validate token")
             }
             AuthError::InvalidClaims => (StatusCode::UNAUTHORIZED, "No valid claims found"),
+            AuthError::InvalidApiToken => (StatusCode::UNAUTHORIZED, "Invalid API token"),
         };
         let body = Json(json!({
             "error": error_message,
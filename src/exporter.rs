@@ -0,0 +1,98 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    formats::{App, Folder, Link, APPLICATIONS_FOLDER},
+    models::{application, bookmark, category, user::User},
+    notes::Notes,
+};
+
+/// Description of exported link, along with item's notes.
+fn describe(description: Option<String>, notes: Option<Notes>) -> Option<String> {
+    match (description, notes.map(Notes::into_markdown)) {
+        (Some(description), Some(notes)) => Some(format!("{description}\n\n{notes}")),
+        (description, notes) => description.or(notes),
+    }
+}
+
+/// Collects user's categories with their bookmarks (and optionally applications, as
+/// a separate folder) into folders of links, keeping the order defined by user. Notes
+/// are exported as links' descriptions.
+pub async fn export_folders(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    with_applications: bool,
+) -> anyhow::Result<Vec<Folder>> {
+    let categories = category::fetch_categories(pool, user).await?;
+    let bookmarks = bookmark::fetch_bookmarks(pool, user, None).await?;
+
+    let mut folders: Vec<Folder> = categories
+        .into_iter()
+        .map(|category| Folder {
+            links: bookmarks
+                .iter()
+                .filter(|b| b.category_id == category.id)
+                .map(|b| Link {
+                    name: b.name.clone(),
+                    url: b.url.clone(),
+                    description: describe(None, b.notes.clone()),
+                    icon: b.icon.clone(),
+                    visible: b.visible,
+                    added_at: b.created_at,
+                })
+                .collect(),
+            name: category.name,
+            nested: category.nested,
+        })
+        .collect();
+
+    if with_applications {
+        let applications = application::fetch_own_applications(pool, user).await?;
+        folders.push(Folder {
+            name: APPLICATIONS_FOLDER.to_string(),
+            links: applications
+                .into_iter()
+                .map(|a| Link {
+                    name: a.name,
+                    url: a.url,
+                    description: describe(a.description, a.notes),
+                    icon: a.icon,
+                    visible: a.visible,
+                    added_at: a.created_at,
+                })
+                .collect(),
+            nested: false,
+        });
+    }
+    Ok(folders)
+}
+
+/// Collects user's applications along with names of their categories. Applications
+/// are ordered by position of their categories, uncategorized ones go last.
+pub async fn export_applications(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<App>> {
+    let categories = category::fetch_categories(pool, user).await?;
+    let mut applications = application::fetch_own_applications(pool, user).await?;
+
+    applications.sort_by_key(|a| {
+        a.category_id
+            .and_then(|id| categories.iter().position(|c| c.id == id))
+            .unwrap_or(categories.len())
+    });
+    Ok(applications
+        .into_iter()
+        .map(|a| App {
+            category: a
+                .category_id
+                .and_then(|id| categories.iter().find(|c| c.id == id))
+                .map(|c| c.name.clone()),
+            shared: a.shared,
+            link: Link {
+                name: a.name,
+                url: a.url,
+                description: a.description,
+                icon: a.icon,
+                visible: a.visible,
+                added_at: a.created_at,
+            },
+        })
+        .collect())
+}
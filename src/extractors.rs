@@ -1,22 +1,40 @@
 use alcoholic_jwt::JWKS;
 use axum::{
     async_trait,
-    extract::{FromRef, FromRequestParts},
-    http::request::Parts,
+    extract::{FromRef, FromRequestParts, Query},
+    http::{header, request::Parts},
     Extension,
 };
 use axum_extra::{
     headers::{authorization::Bearer, Authorization},
     TypedHeader,
 };
+use serde::Deserialize;
 use sqlx::SqlitePool;
 
 use crate::{
     errors::AuthError,
     jwt::{self, Claims},
-    models::user::{self, User},
+    models::{
+        token::{self, TokenScope},
+        user::{self, User},
+    },
 };
 
+/// User authenticated either by session or by API token. Token is expected in
+/// `Authorization: Token <secret>` header or, where no header can be set (like with
+/// bookmarklets), in `token` query parameter.
+pub struct ApiUser(pub User);
+
+/// User authenticated either by session or by search-scoped API token, passed the same
+/// ways as with [`ApiUser`]. Tokens of any other scope are rejected.
+pub struct SearchUser(pub User);
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for Claims
 where
@@ -66,6 +84,68 @@ where
     }
 }
 
+/// Finds owner of API token of given scope, sent along with the request.
+async fn token_user<S>(parts: &mut Parts, state: &S, scope: TokenScope) -> Result<User, AuthError>
+where
+    SqlitePool: FromRef<S>,
+    S: Send + Sync,
+{
+    let header = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Token "))
+        .map(|secret| secret.trim().to_string());
+    let secret = match header {
+        Some(secret) => secret,
+        None => Query::<TokenQuery>::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|Query(query)| query.token)
+            .ok_or(AuthError::InvalidApiToken)?,
+    };
+
+    let pool = SqlitePool::from_ref(state);
+    token::find_by_secret(&pool, &secret, scope)
+        .await
+        .map_err(|_| AuthError::InvalidApiToken)?
+        .ok_or(AuthError::InvalidApiToken)
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiUser
+where
+    SqlitePool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<Claims>().is_some() {
+            return Ok(ApiUser(User::from_request_parts(parts, state).await?));
+        }
+        Ok(ApiUser(token_user(parts, state, TokenScope::Api).await?))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for SearchUser
+where
+    SqlitePool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<Claims>().is_some() {
+            return Ok(SearchUser(User::from_request_parts(parts, state).await?));
+        }
+        Ok(SearchUser(
+            token_user(parts, state, TokenScope::Search).await?,
+        ))
+    }
+}
+
 // #[async_trait]
 // impl<S> FromRequestParts<S> for DatabaseConnection
 // where
@@ -0,0 +1,331 @@
+//! Favicon discovery. Once a bookmark or application gets created, its page is looked up
+//! for icons declared with `<link rel="icon">`, `<link rel="apple-touch-icon">` or in web app
+//! manifest, with `/favicon.ico` as the last resort. The largest icon which turns out to be
+//! an image is downloaded and stored along with other icons, deduplicated by content hash.
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::{cmp::Reverse, collections::HashSet};
+use uuid::Uuid;
+
+use crate::{
+    http::{self, Fetched, Outbound},
+    models::icon,
+};
+
+const PAGE_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+const MANIFEST_SIZE_LIMIT: usize = 256 * 1024;
+const ICON_SIZE_LIMIT: usize = 512 * 1024;
+
+/// Size assumed for scalable (SVG) icons.
+const SCALABLE_SIZE: u32 = 1024;
+
+/// Item to assign discovered favicon to.
+#[derive(Debug, Clone, Copy)]
+pub enum IconTarget {
+    Bookmark(Uuid),
+    Application(Uuid),
+}
+
+#[derive(Debug, PartialEq)]
+struct Candidate {
+    url: Url,
+    size: u32,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    icons: Vec<ManifestIcon>,
+}
+
+#[derive(Deserialize)]
+struct ManifestIcon {
+    src: String,
+    sizes: Option<String>,
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+}
+
+/// Largest of declared icon sizes, like `16x16 32x32` or `any`.
+fn declared_size(sizes: Option<&str>, media_type: Option<&str>, fallback: u32) -> u32 {
+    if media_type.is_some_and(|t| t.contains("svg")) {
+        return SCALABLE_SIZE;
+    }
+    sizes
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .filter_map(|size| match size.to_lowercase().as_str() {
+            "any" => Some(SCALABLE_SIZE),
+            size => size.split('x').next()?.parse().ok(),
+        })
+        .max()
+        .unwrap_or(fallback)
+}
+
+/// Icons declared in HTML page, along with URL of web app manifest if there is any.
+fn page_icons(html: &str, page_url: &Url) -> (Vec<Candidate>, Option<Url>) {
+    let document = Html::parse_document(html);
+    let links = Selector::parse("link[rel][href]").expect("Invalid selector");
+    let base_href = Selector::parse("base[href]").expect("Invalid selector");
+
+    let base = document
+        .select(&base_href)
+        .next()
+        .and_then(|base| page_url.join(base.value().attr("href")?).ok())
+        .unwrap_or_else(|| page_url.clone());
+
+    let mut candidates = Vec::new();
+    let mut manifest = None;
+
+    for link in document.select(&links) {
+        let element = link.value();
+        let (Some(rel), Some(href)) = (element.attr("rel"), element.attr("href")) else {
+            continue;
+        };
+        let Ok(url) = base.join(href.trim()) else {
+            continue;
+        };
+        let rel = rel.to_lowercase();
+        let rels: Vec<&str> = rel.split_whitespace().collect();
+        let size = |fallback| declared_size(element.attr("sizes"), element.attr("type"), fallback);
+
+        if rels.contains(&"manifest") {
+            manifest.get_or_insert(url);
+        } else if rels
+            .iter()
+            .any(|r| *r == "apple-touch-icon" || *r == "apple-touch-icon-precomposed")
+        {
+            candidates.push(Candidate {
+                url,
+                size: size(180),
+            });
+        } else if rels.contains(&"icon") {
+            candidates.push(Candidate {
+                url,
+                size: size(32),
+            });
+        }
+    }
+    (candidates, manifest)
+}
+
+fn manifest_icons(json: &[u8], manifest_url: &Url) -> Vec<Candidate> {
+    let Ok(manifest) = serde_json::from_slice::<Manifest>(json) else {
+        return Vec::new();
+    };
+    manifest
+        .icons
+        .into_iter()
+        .filter_map(|icon| {
+            Some(Candidate {
+                url: manifest_url.join(icon.src.trim()).ok()?,
+                size: declared_size(icon.sizes.as_deref(), icon.media_type.as_deref(), 32),
+            })
+        })
+        .collect()
+}
+
+/// Recognizes image by its content, falling back to declared media type.
+pub fn image_type(fetched: &Fetched) -> Option<String> {
+    let body = fetched.body.as_slice();
+    let head = String::from_utf8_lossy(&body[..body.len().min(256)]).to_lowercase();
+
+    let sniffed = if body.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if body.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if body.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if body.starts_with(b"\0\0\x01\0") {
+        Some("image/x-icon")
+    } else if body.starts_with(b"RIFF") && body.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else if head.trim_start().starts_with("<svg")
+        || (head.trim_start().starts_with("<?xml") && head.contains("<svg"))
+    {
+        Some("image/svg+xml")
+    } else {
+        None
+    };
+    match sniffed {
+        Some(media_type) => Some(media_type.to_string()),
+        None => fetched
+            .media_type()
+            .filter(|mt| mt.starts_with("image/") && !body.is_empty()),
+    }
+}
+
+/// Discovers the best icon of given page. Returns icon's media type and content.
+pub async fn discover_icon(
+    outbound: &Outbound,
+    page_url: &Url,
+) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+    let mut candidates = Vec::new();
+    let mut origin = page_url.clone();
+
+    match outbound.fetch(page_url.clone(), PAGE_SIZE_LIMIT).await {
+        Ok(page) if page.status.is_success() => {
+            origin = page.url.clone();
+            let html = String::from_utf8_lossy(&page.body);
+            let (icons, manifest) = page_icons(&html, &page.url);
+            candidates.extend(icons);
+
+            if let Some(manifest_url) = manifest {
+                match outbound
+                    .fetch(manifest_url.clone(), MANIFEST_SIZE_LIMIT)
+                    .await
+                {
+                    Ok(manifest) if manifest.status.is_success() => {
+                        candidates.extend(manifest_icons(&manifest.body, &manifest.url))
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::debug!(error = ?e, "Couldn't fetch web app manifest"),
+                }
+            }
+        }
+        Ok(page) => tracing::debug!(status = ?page.status, url = %page_url, "Page not available"),
+        Err(e) => tracing::debug!(error = ?e, "Couldn't fetch page"),
+    }
+    if let Ok(favicon) = origin.join("/favicon.ico") {
+        candidates.push(Candidate {
+            url: favicon,
+            size: 0,
+        });
+    }
+
+    // the largest icons go first, keeping order of declarations for same sizes
+    candidates.sort_by_key(|c| Reverse(c.size));
+    let mut seen = HashSet::new();
+    candidates.retain(|c| seen.insert(c.url.clone()));
+
+    for candidate in candidates {
+        match outbound.fetch(candidate.url, ICON_SIZE_LIMIT).await {
+            Ok(fetched) if fetched.status.is_success() => {
+                if let Some(media_type) = image_type(&fetched) {
+                    return Ok(Some((media_type, fetched.body)));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => tracing::debug!(error = ?e, "Couldn't fetch icon"),
+        }
+    }
+    Ok(None)
+}
+
+/// Discovers favicon of given URL and assigns it to bookmark or application.
+/// Returns identifier of stored icon, if any was found.
+pub async fn discover_favicon(
+    pool: &SqlitePool,
+    outbound: &Outbound,
+    target: IconTarget,
+    url: &str,
+) -> anyhow::Result<Option<String>> {
+    let Some((media_type, content)) = discover_icon(outbound, &Url::parse(url)?).await? else {
+        return Ok(None);
+    };
+    let icon_id = icon::store_icon(pool, &media_type, &content).await?;
+    match target {
+        IconTarget::Bookmark(id) => icon::set_bookmark_favicon(pool, &id, &icon_id).await?,
+        IconTarget::Application(id) => icon::set_application_favicon(pool, &id, &icon_id).await?,
+    }
+    Ok(Some(icon_id))
+}
+
+/// Discovers favicon in background, so that the item's creation isn't held up.
+pub fn spawn_favicon_discovery(pool: SqlitePool, target: IconTarget, url: String) {
+    tokio::spawn(async move {
+        match discover_favicon(&pool, http::outbound(), target, &url).await {
+            Ok(Some(icon_id)) => tracing::debug!(url, icon_id, "Favicon discovered"),
+            Ok(None) => tracing::debug!(url, "No favicon found"),
+            Err(e) => tracing::warn!(error = ?e, url, "Couldn't discover favicon"),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn takes_largest_declared_size() {
+        assert_eq!(declared_size(Some("16x16 48x48 32x32"), None, 32), 48);
+        assert_eq!(declared_size(Some("any"), None, 32), SCALABLE_SIZE);
+        assert_eq!(
+            declared_size(None, Some("image/svg+xml"), 32),
+            SCALABLE_SIZE
+        );
+        assert_eq!(declared_size(Some("bogus"), Some("image/png"), 180), 180);
+        assert_eq!(declared_size(None, None, 32), 32);
+    }
+
+    #[test]
+    fn finds_icons_declared_in_page() {
+        let html = r#"<html><head>
+            <base href="/static/">
+            <link rel="stylesheet" href="style.css">
+            <link rel="shortcut icon" href="favicon.ico">
+            <link rel="icon" type="image/png" sizes="16x16 64x64" href=" icon.png ">
+            <link rel="apple-touch-icon" href="https://cdn.example.com/touch.png">
+            <link rel="manifest" href="/app.webmanifest">
+            <link rel="manifest" href="/other.webmanifest">
+        </head></html>"#;
+        let (icons, manifest) = page_icons(html, &url("https://example.com/docs/page"));
+
+        assert_eq!(
+            icons,
+            [
+                Candidate {
+                    url: url("https://example.com/static/favicon.ico"),
+                    size: 32
+                },
+                Candidate {
+                    url: url("https://example.com/static/icon.png"),
+                    size: 64
+                },
+                Candidate {
+                    url: url("https://cdn.example.com/touch.png"),
+                    size: 180
+                },
+            ]
+        );
+        assert_eq!(manifest, Some(url("https://example.com/app.webmanifest")));
+    }
+
+    #[test]
+    fn finds_nothing_in_page_without_icons() {
+        let (icons, manifest) = page_icons("<p>Hello", &url("https://example.com/"));
+        assert!(icons.is_empty());
+        assert_eq!(manifest, None);
+    }
+
+    #[test]
+    fn reads_manifest_icons() {
+        let json = br#"{"name": "App", "icons": [
+            {"src": "icons/192.png", "sizes": "192x192", "type": "image/png"},
+            {"src": "/logo.svg", "type": "image/svg+xml"}
+        ]}"#;
+        let icons = manifest_icons(json, &url("https://example.com/app/manifest.json"));
+
+        assert_eq!(
+            icons,
+            [
+                Candidate {
+                    url: url("https://example.com/app/icons/192.png"),
+                    size: 192
+                },
+                Candidate {
+                    url: url("https://example.com/logo.svg"),
+                    size: SCALABLE_SIZE
+                },
+            ]
+        );
+        assert!(manifest_icons(b"not json", &url("https://example.com/")).is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+//! CSV spreadsheets with one link per row:
+//!
+//! ```csv
+//! category,name,url,description,visible,added_at
+//! Work,Grafana,https://grafana.local,Production dashboards,true,2020-03-10T20:00:00Z
+//! ```
+//!
+//! Columns are matched by header names (case-insensitively), which may be remapped with
+//! [`CsvOptions`] to read spreadsheets produced by other tools. Files without a header
+//! are supported as well, columns are then referred to by their 0-based indexes.
+//!
+//! Only `url` column is required. Rows with no category land in [`UNSORTED_FOLDER`],
+//! missing visibility means a visible link. Exported spreadsheets keep the column order
+//! shown above.
+
+use serde::Deserialize;
+use time::format_description::well_known::Rfc3339;
+
+use super::{parse_date, Folder, Link, UNSORTED_FOLDER};
+
+/// Mapping of CSV columns along with spreadsheet's flavour. Columns are given either
+/// by header names or by 0-based indexes.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CsvOptions {
+    pub category: String,
+    pub name: String,
+    pub url: String,
+    pub description: String,
+    pub visible: String,
+    pub added_at: String,
+    pub delimiter: char,
+    pub headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            category: "category".to_string(),
+            name: "name".to_string(),
+            url: "url".to_string(),
+            description: "description".to_string(),
+            visible: "visible".to_string(),
+            added_at: "added_at".to_string(),
+            delimiter: ',',
+            headers: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    fn delimiter(&self) -> anyhow::Result<u8> {
+        u8::try_from(self.delimiter)
+            .ok()
+            .filter(u8::is_ascii)
+            .ok_or_else(|| anyhow::anyhow!("delimiter has to be a single ASCII character"))
+    }
+}
+
+/// Positions of mapped columns within a row.
+struct Columns {
+    category: Option<usize>,
+    name: Option<usize>,
+    url: usize,
+    description: Option<usize>,
+    visible: Option<usize>,
+    added_at: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(
+        options: &CsvOptions,
+        headers: Option<&::csv::StringRecord>,
+    ) -> anyhow::Result<Self> {
+        let find = |column: &str| match headers {
+            Some(headers) => headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(column.trim()))
+                .or_else(|| column.trim().parse().ok()),
+            None => column.trim().parse().ok(),
+        };
+        Ok(Columns {
+            url: find(&options.url)
+                .ok_or_else(|| anyhow::anyhow!("no '{}' column found", options.url))?,
+            category: find(&options.category),
+            name: find(&options.name),
+            description: find(&options.description),
+            visible: find(&options.visible),
+            added_at: find(&options.added_at),
+        })
+    }
+}
+
+fn parse_visible(value: &str) -> bool {
+    !matches!(
+        value.trim().to_lowercase().as_str(),
+        "false" | "0" | "no" | "n" | "hidden"
+    )
+}
+
+/// Parses CSV spreadsheet into a list of folders, in order of their appearance.
+pub fn parse(content: &str, options: &CsvOptions) -> anyhow::Result<Vec<Folder>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(options.delimiter()?)
+        .has_headers(options.headers)
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = if options.headers {
+        Some(reader.headers()?.clone())
+    } else {
+        None
+    };
+    let columns = Columns::resolve(options, headers.as_ref())?;
+    let mut folders: Vec<Folder> = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let field = |idx: Option<usize>| {
+            idx.and_then(|i| record.get(i))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let Some(url) = field(Some(columns.url)) else {
+            continue;
+        };
+        let link = Link {
+            name: field(columns.name).unwrap_or_default(),
+            url,
+            description: field(columns.description),
+            icon: None,
+            visible: field(columns.visible).is_none_or(|v| parse_visible(&v)),
+            added_at: field(columns.added_at).and_then(|v| parse_date(&v)),
+        };
+        let category = field(columns.category).unwrap_or_else(|| UNSORTED_FOLDER.to_string());
+
+        match folders.iter_mut().find(|f| f.name == category) {
+            Some(folder) => folder.links.push(link),
+            None => folders.push(Folder {
+                name: category,
+                links: vec![link],
+                nested: false,
+            }),
+        }
+    }
+    Ok(folders)
+}
+
+/// Renders folders as CSV spreadsheet, one link per row. Header uses column names given
+/// in options (and is omitted if options say there are no headers).
+pub fn render(folders: &[Folder], options: &CsvOptions) -> anyhow::Result<String> {
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(options.delimiter()?)
+        .from_writer(Vec::new());
+
+    if options.headers {
+        writer.write_record([
+            &options.category,
+            &options.name,
+            &options.url,
+            &options.description,
+            &options.visible,
+            &options.added_at,
+        ])?;
+    }
+    for folder in folders {
+        for link in &folder.links {
+            writer.write_record([
+                folder.name.as_str(),
+                &link.name,
+                &link.url,
+                link.description.as_deref().unwrap_or_default(),
+                if link.visible { "true" } else { "false" },
+                &link
+                    .added_at
+                    .and_then(|a| a.format(&Rfc3339).ok())
+                    .unwrap_or_default(),
+            ])?;
+        }
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    fn link(name: &str, url: &str, visible: bool, added_at: Option<i64>) -> Link {
+        Link {
+            name: name.to_string(),
+            url: url.to_string(),
+            description: None,
+            icon: None,
+            visible,
+            added_at: added_at.map(|ts| OffsetDateTime::from_unix_timestamp(ts).unwrap()),
+        }
+    }
+
+    fn folders() -> Vec<Folder> {
+        vec![
+            Folder {
+                name: "Work".to_string(),
+                links: vec![
+                    Link {
+                        description: Some("Production dashboards, mostly".to_string()),
+                        ..link("Grafana", "https://grafana.local", true, Some(1583870400))
+                    },
+                    link("Prometheus", "https://prometheus.local", false, None),
+                ],
+                nested: false,
+            },
+            Folder {
+                name: UNSORTED_FOLDER.to_string(),
+                links: vec![link("Example", "https://example.com", true, None)],
+                nested: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn round_trips() {
+        let options = CsvOptions::default();
+        let rendered = render(&folders(), &options).unwrap();
+
+        assert!(rendered.starts_with("category,name,url,description,visible,added_at\n"));
+        assert_eq!(parse(&rendered, &options).unwrap(), folders());
+    }
+
+    #[test]
+    fn round_trips_with_custom_mapping_and_delimiter() {
+        let options = CsvOptions {
+            category: "Folder".to_string(),
+            name: "Title".to_string(),
+            url: "Link".to_string(),
+            description: "Note".to_string(),
+            visible: "Shown".to_string(),
+            added_at: "Created".to_string(),
+            delimiter: ';',
+            headers: true,
+        };
+        let rendered = render(&folders(), &options).unwrap();
+
+        assert!(rendered.starts_with("Folder;Title;Link;Note;Shown;Created\n"));
+        assert_eq!(parse(&rendered, &options).unwrap(), folders());
+    }
+
+    #[test]
+    fn round_trips_without_headers() {
+        let options = CsvOptions {
+            category: "0".to_string(),
+            name: "1".to_string(),
+            url: "2".to_string(),
+            description: "3".to_string(),
+            visible: "4".to_string(),
+            added_at: "5".to_string(),
+            delimiter: '\t',
+            headers: false,
+        };
+        let rendered = render(&folders(), &options).unwrap();
+
+        assert!(rendered.starts_with("Work\tGrafana\thttps://grafana.local\t"));
+        assert_eq!(parse(&rendered, &options).unwrap(), folders());
+    }
+
+    #[test]
+    fn maps_columns_of_other_tools() {
+        let options = CsvOptions {
+            category: "Folder".to_string(),
+            name: "Title".to_string(),
+            url: "Link".to_string(),
+            ..Default::default()
+        };
+        let content = "Title,Link,Folder,Created\n\
+                       Grafana,https://grafana.local,Work,1583870400\n\
+                       Example,https://example.com,,\n";
+
+        let folders = parse(content, &options).unwrap();
+        assert_eq!(folders.len(), 2);
+        assert_eq!(folders[0].name, "Work");
+        assert_eq!(folders[0].links[0].name, "Grafana");
+        assert_eq!(folders[1].name, UNSORTED_FOLDER);
+        assert!(folders[1].links[0].visible);
+    }
+
+    #[test]
+    fn requires_url_column() {
+        let options = CsvOptions::default();
+
+        assert!(parse("name,link\nExample,https://example.com\n", &options).is_err());
+    }
+
+    #[test]
+    fn rejects_non_ascii_delimiter() {
+        let options = CsvOptions {
+            delimiter: '→',
+            ..Default::default()
+        };
+
+        assert!(parse("url\nhttps://example.com\n", &options).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! [Dashy](https://github.com/Lissy93/dashy) dashboards, defined in Dashy's `conf.yml`:
+//!
+//! ```yaml
+//! pageInfo:
+//!   title: Homelab
+//! sections:
+//!   - name: Monitoring
+//!     icon: fas fa-heartbeat
+//!     items:
+//!       - title: Grafana
+//!         description: Metrics dashboards
+//!         icon: https://grafana.local/public/img/grafana_icon.svg
+//!         url: https://grafana.local
+//! ```
+//!
+//! Dashy's sections become categories of imported applications. Item's icon (be it
+//! an URL, Font Awesome class or any of Dashy's icon shorthands) is carried over as is.
+//!
+//! Dashy has no notion of bookmarks, only applications are imported and exported.
+
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, sections, App, Dashboard, Link};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DashyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_info: Option<DashyPageInfo>,
+    pub sections: Vec<DashySection>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DashyPageInfo {
+    pub title: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DashySection {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub items: Vec<DashyItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DashyItem {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub url: String,
+}
+
+/// Parses Dashy's `conf.yml`.
+pub fn parse(yaml: &str) -> anyhow::Result<Dashboard> {
+    let config: DashyConfig = serde_yaml::from_str(yaml)?;
+    let applications = config
+        .sections
+        .into_iter()
+        .flat_map(|section| {
+            let category = non_empty(Some(section.name));
+            section.items.into_iter().map(move |item| App {
+                link: Link {
+                    name: item.title,
+                    url: item.url.trim().to_string(),
+                    description: non_empty(item.description),
+                    icon: non_empty(item.icon),
+                    visible: true,
+                    added_at: None,
+                },
+                category: category.clone(),
+                shared: false,
+            })
+        })
+        .collect();
+
+    Ok(Dashboard {
+        applications,
+        folders: Vec::new(),
+    })
+}
+
+/// Renders applications as Dashy's `conf.yml`, one section per category.
+pub fn render(applications: Vec<App>) -> anyhow::Result<String> {
+    let sections = sections(applications)
+        .into_iter()
+        .map(|(name, apps)| DashySection {
+            name,
+            icon: None,
+            items: apps
+                .into_iter()
+                .map(|app| DashyItem {
+                    title: app.link.name,
+                    description: app.link.description,
+                    icon: app.link.icon,
+                    url: app.link.url,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(serde_yaml::to_string(&DashyConfig {
+        page_info: Some(DashyPageInfo {
+            title: "trufel".to_string(),
+        }),
+        sections,
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::APPLICATIONS_FOLDER;
+
+    #[test]
+    fn parses_sections() {
+        let dashboard = parse(
+            r#"
+pageInfo:
+  title: Homelab
+sections:
+  - name: Monitoring
+    icon: fas fa-heartbeat
+    items:
+      - title: Grafana
+        description: Metrics dashboards
+        icon: hl-grafana
+        url: https://grafana.local
+      - title: Prometheus
+        url: " https://prometheus.local "
+  - name: ""
+    items:
+      - title: Wiki
+        description: "  "
+        url: https://wiki.local
+"#,
+        )
+        .unwrap();
+
+        let apps: Vec<_> = dashboard
+            .applications
+            .iter()
+            .map(|a| {
+                (
+                    a.link.url.as_str(),
+                    a.link.description.as_deref(),
+                    a.link.icon.as_deref(),
+                    a.category.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            apps,
+            [
+                (
+                    "https://grafana.local",
+                    Some("Metrics dashboards"),
+                    Some("hl-grafana"),
+                    Some("Monitoring")
+                ),
+                ("https://prometheus.local", None, None, Some("Monitoring")),
+                ("https://wiki.local", None, None, None),
+            ]
+        );
+        assert!(dashboard.folders.is_empty());
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse("sections: {name: 12}").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let applications = vec![App {
+            link: Link {
+                name: "Grafana".to_string(),
+                url: "https://grafana.local".to_string(),
+                description: Some("Metrics".to_string()),
+                icon: Some("fas fa-chart-line".to_string()),
+                visible: true,
+                added_at: None,
+            },
+            category: Some("Monitoring".to_string()),
+            shared: false,
+        }];
+        let rendered = render(applications.clone()).unwrap();
+
+        assert_eq!(parse(&rendered).unwrap().applications, applications);
+    }
+
+    #[test]
+    fn renders_uncategorized_applications_in_their_own_section() {
+        let rendered = render(vec![App {
+            link: Link {
+                name: "Wiki".to_string(),
+                url: "https://wiki.local".to_string(),
+                description: None,
+                icon: None,
+                visible: true,
+                added_at: None,
+            },
+            category: None,
+            shared: false,
+        }])
+        .unwrap();
+
+        assert_eq!(
+            parse(&rendered).unwrap().applications[0]
+                .category
+                .as_deref(),
+            Some(APPLICATIONS_FOLDER)
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! [Flame](https://github.com/pawelmalak/flame) dashboards, either read directly from Flame's
+//! SQLite database (`data/db.sqlite`) or from JSON document shaped like Flame's API responses:
+//!
+//! ```json
+//! {
+//!   "apps": [{"name": "Grafana", "url": "grafana.local", "icon": "chartLine",
+//!             "isPinned": true, "isPublic": false, "orderId": 1}],
+//!   "categories": [{"id": 1, "name": "Docs", "isPinned": true, "orderId": 1,
+//!                   "bookmarks": [{"name": "docs.rs", "url": "https://docs.rs",
+//!                                  "categoryId": 1, "icon": "", "isPublic": true}]}],
+//!   "bookmarks": []
+//! }
+//! ```
+//!
+//! Flame's flags are mapped as following:
+//! - pinned apps (the ones shown on Flame's home screen) become visible applications,
+//! - public apps (visible to Flame's guests) become applications shared with others,
+//! - bookmarks of pinned categories are visible, the ones of unpinned categories are hidden.
+//!
+//! Flame's icons are Material Design icon names or files of custom icons, neither of which
+//! trufel is able to show (icons hold SVG markup), so only SVG markup is carried over.
+
+use serde::{Deserialize, Deserializer};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteRow},
+    Connection, Row, SqliteConnection,
+};
+use std::path::Path;
+
+use super::{App, Dashboard, Folder, Link, UNSORTED_FOLDER};
+
+/// Header of every SQLite database file.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlameApp {
+    pub name: String,
+    pub url: String,
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    #[serde(deserialize_with = "flag")]
+    pub is_pinned: bool,
+    #[serde(deserialize_with = "flag")]
+    pub is_public: bool,
+    pub order_id: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlameCategory {
+    pub id: i64,
+    pub name: String,
+    #[serde(deserialize_with = "flag")]
+    pub is_pinned: bool,
+    pub order_id: Option<i64>,
+    pub bookmarks: Vec<FlameBookmark>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlameBookmark {
+    pub name: String,
+    pub url: String,
+    pub category_id: Option<i64>,
+    pub icon: Option<String>,
+    pub order_id: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct FlameExport {
+    pub apps: Vec<FlameApp>,
+    pub categories: Vec<FlameCategory>,
+    pub bookmarks: Vec<FlameBookmark>,
+}
+
+/// Flame stores flags as booleans in JSON responses but as integers in SQLite dumps.
+fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Int(i64),
+    }
+    Ok(match Option::<Flag>::deserialize(deserializer)? {
+        Some(Flag::Bool(b)) => b,
+        Some(Flag::Int(i)) => i != 0,
+        None => false,
+    })
+}
+
+/// Flame accepts app URLs without scheme, assuming http.
+fn absolute_url(url: &str) -> String {
+    let url = url.trim();
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{url}")
+    }
+}
+
+fn icon(icon: Option<String>) -> Option<String> {
+    icon.map(|i| i.trim().to_string())
+        .filter(|i| i.starts_with("<svg"))
+}
+
+impl FlameExport {
+    /// Converts Flame's data into a dashboard, respecting Flame's ordering.
+    pub fn into_dashboard(mut self) -> Dashboard {
+        self.apps.sort_by_key(|a| a.order_id);
+        self.categories.sort_by_key(|c| c.order_id);
+
+        let applications = self
+            .apps
+            .into_iter()
+            .map(|app| App {
+                link: Link {
+                    url: absolute_url(&app.url),
+                    name: app.name,
+                    description: app.description.filter(|d| !d.trim().is_empty()),
+                    icon: icon(app.icon),
+                    visible: app.is_pinned,
+                    added_at: None,
+                },
+                category: None,
+                shared: app.is_public,
+            })
+            .collect();
+
+        // bookmarks may come either nested in categories or as a separate list
+        let mut loose = self.bookmarks;
+        let mut folders: Vec<Folder> = self
+            .categories
+            .into_iter()
+            .map(|category| {
+                let id = category.id;
+                let mut bookmarks = category.bookmarks;
+                bookmarks.extend(loose.extract_if(.., |b| b.category_id == Some(id)));
+                bookmarks.sort_by_key(|b| b.order_id);
+
+                Folder {
+                    name: category.name,
+                    links: bookmarks
+                        .into_iter()
+                        .map(|b| bookmark_link(b, category.is_pinned))
+                        .collect(),
+                    nested: false,
+                }
+            })
+            .collect();
+
+        if !loose.is_empty() {
+            loose.sort_by_key(|b| b.order_id);
+            folders.push(Folder {
+                name: UNSORTED_FOLDER.to_string(),
+                links: loose.into_iter().map(|b| bookmark_link(b, true)).collect(),
+                nested: false,
+            });
+        }
+        Dashboard {
+            applications,
+            folders,
+        }
+    }
+}
+
+fn bookmark_link(bookmark: FlameBookmark, visible: bool) -> Link {
+    Link {
+        url: absolute_url(&bookmark.url),
+        name: bookmark.name,
+        description: None,
+        icon: icon(bookmark.icon),
+        visible,
+        added_at: None,
+    }
+}
+
+/// Checks whether given content is an SQLite database rather than JSON document.
+pub fn is_sqlite(content: &[u8]) -> bool {
+    content.starts_with(SQLITE_HEADER)
+}
+
+/// Parses JSON document with Flame's apps, categories and bookmarks.
+pub fn parse_json(json: &str) -> anyhow::Result<Dashboard> {
+    let export: FlameExport = serde_json::from_str(json)?;
+    Ok(export.into_dashboard())
+}
+
+/// Reads Flame's apps, categories and bookmarks directly from Flame's SQLite database.
+/// Database is opened in read-only mode.
+pub async fn read_sqlite(path: &Path) -> anyhow::Result<Dashboard> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    // columns differ between Flame versions, hence no typed queries
+    let text = |row: &SqliteRow, col: &str| row.try_get::<Option<String>, _>(col).ok().flatten();
+    let number = |row: &SqliteRow, col: &str| row.try_get::<Option<i64>, _>(col).ok().flatten();
+    let flag = |row: &SqliteRow, col: &str| number(row, col).is_some_and(|n| n != 0);
+
+    let apps = sqlx::query("SELECT * FROM apps")
+        .fetch_all(&mut conn)
+        .await?
+        .iter()
+        .map(|row| FlameApp {
+            name: text(row, "name").unwrap_or_default(),
+            url: text(row, "url").unwrap_or_default(),
+            icon: text(row, "icon"),
+            description: text(row, "description"),
+            is_pinned: flag(row, "isPinned"),
+            is_public: flag(row, "isPublic"),
+            order_id: number(row, "orderId"),
+        })
+        .collect();
+
+    let categories = sqlx::query("SELECT * FROM categories")
+        .fetch_all(&mut conn)
+        .await?
+        .iter()
+        .map(|row| FlameCategory {
+            id: number(row, "id").unwrap_or_default(),
+            name: text(row, "name").unwrap_or_default(),
+            is_pinned: flag(row, "isPinned"),
+            order_id: number(row, "orderId"),
+            bookmarks: Vec::new(),
+        })
+        .collect();
+
+    let bookmarks = sqlx::query("SELECT * FROM bookmarks")
+        .fetch_all(&mut conn)
+        .await?
+        .iter()
+        .map(|row| FlameBookmark {
+            name: text(row, "name").unwrap_or_default(),
+            url: text(row, "url").unwrap_or_default(),
+            category_id: number(row, "categoryId"),
+            icon: text(row, "icon"),
+            order_id: number(row, "orderId"),
+        })
+        .collect();
+
+    conn.close().await?;
+
+    Ok(FlameExport {
+        apps,
+        categories,
+        bookmarks,
+    }
+    .into_dashboard())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "apps": [
+            {"name": "Wiki", "url": "https://wiki.local", "icon": "bookOpen",
+             "isPinned": false, "isPublic": 1, "orderId": 2},
+            {"name": "Grafana", "url": " grafana.local ", "icon": "<svg></svg>",
+             "description": " ", "isPinned": true, "isPublic": false, "orderId": 1}
+        ],
+        "categories": [
+            {"id": 2, "name": "Archive", "isPinned": false, "orderId": 2, "bookmarks": []},
+            {"id": 1, "name": "Docs", "isPinned": 1, "orderId": 1, "bookmarks": [
+                {"name": "docs.rs", "url": "https://docs.rs", "categoryId": 1, "orderId": 2}
+            ]}
+        ],
+        "bookmarks": [
+            {"name": "crates.io", "url": "crates.io", "categoryId": 1, "orderId": 1},
+            {"name": "Old", "url": "https://old.local", "categoryId": 2},
+            {"name": "Loose", "url": "https://loose.local", "categoryId": 9}
+        ]
+    }"#;
+
+    #[test]
+    fn parses_apps_in_order() {
+        let dashboard = parse_json(EXPORT).unwrap();
+
+        let apps: Vec<_> = dashboard
+            .applications
+            .iter()
+            .map(|a| {
+                (
+                    a.link.url.as_str(),
+                    a.link.description.as_deref(),
+                    a.link.icon.as_deref(),
+                    a.link.visible,
+                    a.shared,
+                )
+            })
+            .collect();
+        assert_eq!(
+            apps,
+            [
+                (
+                    "http://grafana.local",
+                    None,
+                    Some("<svg></svg>"),
+                    true,
+                    false
+                ),
+                ("https://wiki.local", None, None, false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_bookmarks_into_categories() {
+        let dashboard = parse_json(EXPORT).unwrap();
+
+        let folders: Vec<_> = dashboard
+            .folders
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.links
+                        .iter()
+                        .map(|l| (l.url.as_str(), l.visible))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            folders,
+            [
+                (
+                    "Docs",
+                    vec![("http://crates.io", true), ("https://docs.rs", true)]
+                ),
+                ("Archive", vec![("https://old.local", false)]),
+                (UNSORTED_FOLDER, vec![("https://loose.local", true)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tells_sqlite_databases_apart() {
+        assert!(is_sqlite(b"SQLite format 3\0\x10\0"));
+        assert!(!is_sqlite(EXPORT.as_bytes()));
+        assert!(parse_json("SQLite format 3").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! [Heimdall](https://github.com/linuxserver/Heimdall) application dashboards, as exported
+//! by Heimdall's settings page - JSON array of items:
+//!
+//! ```json
+//! [{"title": "Grafana", "colour": "#161b1f", "url": "https://grafana.local",
+//!   "description": null, "appid": null, "appdescription": null, "pinned": 1}]
+//! ```
+//!
+//! Heimdall's pinned items (the ones shown on Heimdall's home screen) become visible
+//! applications. Items with no pinning information are assumed to be pinned. Heimdall
+//! doesn't group its items in an exported form, hence no categories are assigned.
+//!
+//! Heimdall has no notion of bookmarks, only applications are imported and exported.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{non_empty, App, Dashboard, Link};
+
+/// Heimdall's default tile colour.
+const DEFAULT_COLOUR: &str = "#161b1f";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HeimdallItem {
+    pub title: String,
+    pub colour: Option<String>,
+    pub icon: Option<String>,
+    pub url: String,
+    pub description: Option<String>,
+    pub appid: Option<String>,
+    pub appdescription: Option<String>,
+    #[serde(default = "pinned_by_default", deserialize_with = "pinned")]
+    pub pinned: bool,
+}
+
+fn pinned_by_default() -> bool {
+    true
+}
+
+/// Heimdall stores pinning flag as an integer. Missing flag means item is pinned.
+fn pinned<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Int(i64),
+    }
+    Ok(match Option::<Flag>::deserialize(deserializer)? {
+        Some(Flag::Bool(b)) => b,
+        Some(Flag::Int(i)) => i != 0,
+        None => true,
+    })
+}
+
+/// Parses JSON array of Heimdall's items.
+pub fn parse(json: &str) -> anyhow::Result<Dashboard> {
+    let items: Vec<HeimdallItem> = serde_json::from_str(json)?;
+    let applications = items
+        .into_iter()
+        .map(|item| App {
+            link: Link {
+                name: item.title,
+                url: item.url.trim().to_string(),
+                description: non_empty(item.description).or(non_empty(item.appdescription)),
+                icon: non_empty(item.icon),
+                visible: item.pinned,
+                added_at: None,
+            },
+            category: None,
+            shared: false,
+        })
+        .collect();
+
+    Ok(Dashboard {
+        applications,
+        folders: Vec::new(),
+    })
+}
+
+/// Renders applications as JSON array of Heimdall's items.
+pub fn render(applications: Vec<App>) -> anyhow::Result<String> {
+    let items: Vec<HeimdallItem> = applications
+        .into_iter()
+        .map(|app| HeimdallItem {
+            title: app.link.name,
+            colour: Some(DEFAULT_COLOUR.to_string()),
+            icon: app.link.icon.filter(|i| !i.trim_start().starts_with('<')),
+            url: app.link.url,
+            description: app.link.description,
+            appid: None,
+            appdescription: None,
+            pinned: app.link.visible,
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&items)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_items() {
+        let dashboard = parse(
+            r##"[
+                {"title": "Grafana", "colour": "#161b1f", "url": " https://grafana.local ",
+                 "description": " ", "appid": null, "appdescription": "Metrics", "pinned": 1},
+                {"title": "Wiki", "url": "https://wiki.local", "pinned": 0, "icon": "wiki.png"},
+                {"title": "Git", "url": "https://git.local", "description": "Code", "pinned": false},
+                {"title": "Mail", "url": "https://mail.local"}
+            ]"##,
+        )
+        .unwrap();
+
+        let apps: Vec<_> = dashboard
+            .applications
+            .iter()
+            .map(|a| {
+                (
+                    a.link.url.as_str(),
+                    a.link.description.as_deref(),
+                    a.link.icon.as_deref(),
+                    a.link.visible,
+                )
+            })
+            .collect();
+        assert_eq!(
+            apps,
+            [
+                ("https://grafana.local", Some("Metrics"), None, true),
+                ("https://wiki.local", None, Some("wiki.png"), false),
+                ("https://git.local", Some("Code"), None, false),
+                ("https://mail.local", None, None, true),
+            ]
+        );
+        assert!(dashboard.applications.iter().all(|a| a.category.is_none()));
+        assert!(dashboard.folders.is_empty());
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse(r#"{"title": "Grafana"}"#).is_err());
+        assert!(parse("").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let dashboard = parse(
+            r#"[{"title": "Grafana", "url": "https://grafana.local", "description": "Metrics",
+                 "icon": "grafana.png", "pinned": 0},
+                {"title": "Wiki", "url": "https://wiki.local"}]"#,
+        )
+        .unwrap();
+        let rendered = render(dashboard.applications.clone()).unwrap();
+
+        assert_eq!(parse(&rendered).unwrap(), dashboard);
+    }
+
+    #[test]
+    fn leaves_inline_svg_icons_out() {
+        let rendered = render(vec![App {
+            link: Link {
+                name: "Grafana".to_string(),
+                url: "https://grafana.local".to_string(),
+                description: None,
+                icon: Some("<svg></svg>".to_string()),
+                visible: true,
+                added_at: None,
+            },
+            category: None,
+            shared: false,
+        }])
+        .unwrap();
+
+        assert_eq!(parse(&rendered).unwrap().applications[0].link.icon, None);
+    }
+}
@@ -0,0 +1,245 @@
+//! [Homer](https://github.com/bastienwirtz/homer) dashboards, defined in Homer's `config.yml`:
+//!
+//! ```yaml
+//! title: "Homelab"
+//! services:
+//!   - name: "Monitoring"
+//!     icon: "fas fa-heartbeat"
+//!     items:
+//!       - name: "Grafana"
+//!         logo: "assets/tools/grafana.png"
+//!         subtitle: "Metrics dashboards"
+//!         url: "https://grafana.local"
+//! ```
+//!
+//! Homer's service groups become categories of imported applications. Items' subtitles
+//! become applications' descriptions. Both Homer's `logo` (image path or URL) and `icon`
+//! (Font Awesome class) are carried over as application's icon, logo taking precedence.
+//!
+//! Homer has no notion of bookmarks, only applications are imported and exported.
+
+use serde::{Deserialize, Serialize};
+
+use super::{non_empty, sections, App, Dashboard, Link};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HomerConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub services: Vec<HomerService>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HomerService {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    pub items: Vec<HomerItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HomerItem {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    pub url: String,
+}
+
+/// Font Awesome classes (like `fas fa-heartbeat`) go to Homer's `icon`, everything else
+/// is assumed to be an image and goes to Homer's `logo`. Inline SVGs are not supported
+/// by Homer and are left out.
+fn homer_icon(icon: Option<String>) -> (Option<String>, Option<String>) {
+    match icon {
+        Some(icon) if icon.trim_start().starts_with('<') => (None, None),
+        Some(icon) if icon.split_whitespace().any(|c| c.starts_with("fa-")) => (None, Some(icon)),
+        logo => (logo, None),
+    }
+}
+
+/// Parses Homer's `config.yml`.
+pub fn parse(yaml: &str) -> anyhow::Result<Dashboard> {
+    let config: HomerConfig = serde_yaml::from_str(yaml)?;
+    let applications = config
+        .services
+        .into_iter()
+        .flat_map(|service| {
+            let category = non_empty(Some(service.name));
+            service.items.into_iter().map(move |item| App {
+                link: Link {
+                    name: item.name,
+                    url: item.url.trim().to_string(),
+                    description: non_empty(item.subtitle),
+                    icon: non_empty(item.logo).or(non_empty(item.icon)),
+                    visible: true,
+                    added_at: None,
+                },
+                category: category.clone(),
+                shared: false,
+            })
+        })
+        .collect();
+
+    Ok(Dashboard {
+        applications,
+        folders: Vec::new(),
+    })
+}
+
+/// Renders applications as Homer's `config.yml`, one service group per category.
+pub fn render(applications: Vec<App>) -> anyhow::Result<String> {
+    let services = sections(applications)
+        .into_iter()
+        .map(|(name, apps)| HomerService {
+            name,
+            icon: None,
+            items: apps
+                .into_iter()
+                .map(|app| {
+                    let (logo, icon) = homer_icon(app.link.icon);
+                    HomerItem {
+                        name: app.link.name,
+                        logo,
+                        icon,
+                        subtitle: app.link.description,
+                        url: app.link.url,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(serde_yaml::to_string(&HomerConfig {
+        title: Some("trufel".to_string()),
+        services,
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+title: "Homelab"
+services:
+  - name: "Monitoring"
+    icon: "fas fa-heartbeat"
+    items:
+      - name: "Grafana"
+        logo: "assets/tools/grafana.png"
+        icon: "fas fa-chart-line"
+        subtitle: "Metrics dashboards"
+        url: "https://grafana.local"
+      - name: "Prometheus"
+        icon: "fas fa-fire"
+        url: "https://prometheus.local"
+  - name: " "
+    items:
+      - name: "Wiki"
+        subtitle: ""
+        url: " https://wiki.local "
+"#;
+
+    fn app(name: &str, icon: Option<&str>, category: &str) -> App {
+        App {
+            link: Link {
+                name: name.to_string(),
+                url: format!("https://{}.local", name.to_lowercase()),
+                description: None,
+                icon: icon.map(String::from),
+                visible: true,
+                added_at: None,
+            },
+            category: Some(category.to_string()),
+            shared: false,
+        }
+    }
+
+    #[test]
+    fn parses_services() {
+        let dashboard = parse(CONFIG).unwrap();
+
+        let apps: Vec<_> = dashboard
+            .applications
+            .iter()
+            .map(|a| {
+                (
+                    a.link.url.as_str(),
+                    a.link.description.as_deref(),
+                    a.link.icon.as_deref(),
+                    a.category.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            apps,
+            [
+                (
+                    "https://grafana.local",
+                    Some("Metrics dashboards"),
+                    Some("assets/tools/grafana.png"),
+                    Some("Monitoring")
+                ),
+                (
+                    "https://prometheus.local",
+                    None,
+                    Some("fas fa-fire"),
+                    Some("Monitoring")
+                ),
+                ("https://wiki.local", None, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(parse("services: 12").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let applications = vec![
+            app("Grafana", Some("assets/grafana.png"), "Monitoring"),
+            app("Prometheus", Some("fas fa-fire"), "Monitoring"),
+            app("Wiki", None, "Docs"),
+        ];
+        let rendered = render(applications.clone()).unwrap();
+
+        assert_eq!(parse(&rendered).unwrap().applications, applications);
+    }
+
+    #[test]
+    fn renders_icons_as_logos_or_classes() {
+        let rendered = render(vec![
+            app(
+                "Grafana",
+                Some("https://grafana.local/logo.png"),
+                "Monitoring",
+            ),
+            app("Prometheus", Some("fas fa-fire"), "Monitoring"),
+            app("Wiki", Some("<svg></svg>"), "Monitoring"),
+        ])
+        .unwrap();
+        let config: HomerConfig = serde_yaml::from_str(&rendered).unwrap();
+        let items = &config.services[0].items;
+
+        assert_eq!(
+            (items[0].logo.as_deref(), items[0].icon.as_deref()),
+            (Some("https://grafana.local/logo.png"), None)
+        );
+        assert_eq!(
+            (items[1].logo.as_deref(), items[1].icon.as_deref()),
+            (None, Some("fas fa-fire"))
+        );
+        assert_eq!(
+            (items[2].logo.as_deref(), items[2].icon.as_deref()),
+            (None, None)
+        );
+    }
+}
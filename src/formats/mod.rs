@@ -0,0 +1,273 @@
+//! Readers (and writers) of link collections exchanged with browsers and other launchers.
+//!
+//! Each format is converted into a common, format-agnostic representation of [`Folder`]s
+//! holding [`Link`]s, which is then persisted by the [`crate::importer`]. Exports go the
+//! other way round - [`crate::exporter`] collects user's data into folders, which are then
+//! rendered in requested format.
+
+pub mod csv;
+pub mod dashy;
+pub mod flame;
+pub mod heimdall;
+pub mod homer;
+pub mod netscape;
+pub mod xbel;
+
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// Name of category collecting links which were not assigned to any folder.
+pub const UNSORTED_FOLDER: &str = "Unsorted";
+
+/// Name of folder collecting applications which were not assigned to any category.
+pub const APPLICATIONS_FOLDER: &str = "Applications";
+
+/// Separator of nested folders' names. Formats supporting nested folders are flattened
+/// into a single level of folders named like `Work / Monitoring`.
+pub const FOLDER_SEPARATOR: &str = " / ";
+
+/// A single link read from imported collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub name: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub visible: bool,
+    pub added_at: Option<OffsetDateTime>,
+}
+
+/// Named group of links, mapped onto trufel's category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Folder {
+    pub name: String,
+    pub links: Vec<Link>,
+    /// Folder flattened from nested folders, named by their path joined with
+    /// [`FOLDER_SEPARATOR`]. Names of other folders are taken literally.
+    pub nested: bool,
+}
+
+/// Launcher's application, mapped onto trufel's application. Applications grouped by
+/// launcher (eg. in Homer's services or Dashy's sections) land in a category of group's name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct App {
+    pub link: Link,
+    pub category: Option<String>,
+    pub shared: bool,
+}
+
+/// Complete dashboard - applications along with folders of links.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dashboard {
+    pub applications: Vec<App>,
+    pub folders: Vec<Folder>,
+}
+
+/// Folder in a tree of nested folders, rebuilt from flattened folders' names.
+#[derive(Default)]
+pub struct Node<'a> {
+    pub name: &'a str,
+    pub links: Vec<&'a Link>,
+    pub children: Vec<Node<'a>>,
+}
+
+impl<'a> Node<'a> {
+    /// Builds a tree of nested folders. Names of nested folders are split by
+    /// [`FOLDER_SEPARATOR`], links of [`UNSORTED_FOLDER`] are put at the top level.
+    pub fn tree(folders: &'a [Folder]) -> Node<'a> {
+        let mut root = Node::default();
+        for folder in folders {
+            let node = if folder.name == UNSORTED_FOLDER {
+                &mut root
+            } else if folder.nested {
+                folder
+                    .name
+                    .split(FOLDER_SEPARATOR)
+                    .fold(&mut root, |node, name| node.child(name))
+            } else {
+                root.child(&folder.name)
+            };
+            node.links.extend(folder.links.iter());
+        }
+        root
+    }
+
+    fn child(&mut self, name: &'a str) -> &mut Node<'a> {
+        let idx = match self.children.iter().position(|n| n.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.children.push(Node {
+                    name,
+                    ..Default::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[idx]
+    }
+}
+
+/// Groups applications by their categories, keeping the order in which categories
+/// appear first. Uncategorized applications are grouped under [`APPLICATIONS_FOLDER`].
+pub fn sections(applications: Vec<App>) -> Vec<(String, Vec<App>)> {
+    let mut sections: Vec<(String, Vec<App>)> = Vec::new();
+    for app in applications {
+        let name = app
+            .category
+            .clone()
+            .unwrap_or_else(|| APPLICATIONS_FOLDER.to_string());
+
+        match sections.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, apps)) => apps.push(app),
+            None => sections.push((name, vec![app])),
+        }
+    }
+    sections
+}
+
+/// Non-empty, trimmed text.
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Text with runs of whitespace (including line breaks) collapsed into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Converts timestamp into a date. Timestamps are expected in seconds since epoch, some
+/// tools however use milli- or microseconds.
+fn parse_timestamp(value: &str) -> Option<OffsetDateTime> {
+    let mut timestamp: i64 = value.trim().parse().ok()?;
+    while timestamp > 100_000_000_000 {
+        timestamp /= 1000;
+    }
+    OffsetDateTime::from_unix_timestamp(timestamp).ok()
+}
+
+/// Accepts RFC 3339 dates as well as timestamps.
+fn parse_date(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+    OffsetDateTime::parse(value, &Rfc3339)
+        .ok()
+        .or_else(|| parse_timestamp(value))
+}
+
+/// Decodes HTML character references (named ones commonly used by exporters and numeric ones).
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest
+            .find(';')
+            .filter(|&semi| semi <= 10)
+            .and_then(|semi| decode_entity(&rest[1..semi]).map(|c| (c, semi)));
+
+        match entity {
+            Some((c, semi)) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Escapes text to be safely embedded into HTML or XML document.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            decode_entities("a &amp; b &lt;c&gt; &#39;d&#x27; &quot;e&quot;"),
+            "a & b <c> 'd' \"e\""
+        );
+        // unknown or unterminated references are kept as they are
+        assert_eq!(
+            decode_entities("AT&T &copy; &#xZZ; &"),
+            "AT&T &copy; &#xZZ; &"
+        );
+        assert_eq!(
+            decode_entities(&escape("<a href=\"x\">'&'</a>")),
+            "<a href=\"x\">'&'</a>"
+        );
+    }
+
+    fn folder(name: &str, nested: bool) -> Folder {
+        Folder {
+            name: name.to_string(),
+            links: Vec::new(),
+            nested,
+        }
+    }
+
+    #[test]
+    fn nests_only_nested_folders() {
+        let folders = [
+            folder(UNSORTED_FOLDER, false),
+            folder("Work / Monitoring", true),
+            folder("Work", false),
+            folder("A / B", false),
+        ];
+        let root = Node::tree(&folders);
+
+        let names: Vec<_> = root.children.iter().map(|n| n.name).collect();
+        assert_eq!(names, ["Work", "A / B"]);
+        assert_eq!(root.children[0].children[0].name, "Monitoring");
+    }
+
+    #[test]
+    fn parses_dates_and_timestamps() {
+        let date = OffsetDateTime::from_unix_timestamp(1583870400).unwrap();
+
+        assert_eq!(parse_date("2020-03-10T20:00:00Z"), Some(date));
+        assert_eq!(parse_date(" 1583870400 "), Some(date));
+        assert_eq!(parse_timestamp("1583870400000"), Some(date));
+        assert_eq!(parse_timestamp("1583870400000000"), Some(date));
+        assert_eq!(parse_date("yesterday"), None);
+    }
+}
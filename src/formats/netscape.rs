@@ -0,0 +1,406 @@
+//! Netscape bookmark file format - the `bookmarks.html` exported by every major browser.
+//!
+//! Format is a loosely structured HTML where folders are `<H3>` headers followed by `<DL>`
+//! lists and links are `<A>` anchors, optionally followed by `<DD>` descriptions:
+//!
+//! ```html
+//! <DL><p>
+//!     <DT><H3 ADD_DATE="1583869200">Work</H3>
+//!     <DL><p>
+//!         <DT><A HREF="https://grafana.local" ADD_DATE="1583869200">Grafana</A>
+//!         <DD>Production dashboards
+//!     </DL><p>
+//! </DL><p>
+//! ```
+//!
+//! As the structure is rarely a valid HTML, it's parsed with a simple, forgiving tokenizer.
+//! Nested folders are flattened into a single level, with names joined by " / ". Rendering
+//! reverses this, so that folders flattened during import get nested again.
+
+use std::fmt::Write;
+
+use super::{
+    collapse_whitespace, decode_entities, escape, parse_timestamp, Folder, Link, Node,
+    FOLDER_SEPARATOR, UNSORTED_FOLDER,
+};
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open(String, Vec<(String, String)>),
+    Close(String),
+    Text(&'a str),
+}
+
+/// Splits document into a sequence of tags and texts. Comments and declarations are skipped.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if lt > 0 {
+            tokens.push(Token::Text(&rest[..lt]));
+        }
+        rest = &rest[lt..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+        } else if let Some(closing) = rest.strip_prefix("</") {
+            let end = closing.find('>').unwrap_or(closing.len());
+            tokens.push(Token::Close(closing[..end].trim().to_lowercase()));
+            rest = closing.get(end + 1..).unwrap_or("");
+        } else {
+            let (token, remainder) = open_tag(&rest[1..]);
+            match token {
+                Some(token) => tokens.push(token),
+                None => tokens.push(Token::Text(&rest[..1])),
+            }
+            rest = remainder;
+        }
+    }
+    tokens
+}
+
+/// Parses opening tag (with already consumed `<`) along with its attributes.
+fn open_tag(input: &str) -> (Option<Token<'_>>, &str) {
+    let name_len = input
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(input.len());
+    if name_len == 0 {
+        return (None, input);
+    }
+    let name = input[..name_len].to_lowercase();
+    let mut attrs = Vec::new();
+    let mut rest = &input[name_len..];
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(remainder) = rest.strip_prefix('>') {
+            rest = remainder;
+            break;
+        }
+        let attr_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let attr = rest[..attr_len].to_lowercase();
+        rest = rest[attr_len..].trim_start();
+
+        let mut value = String::new();
+        if let Some(remainder) = rest.strip_prefix('=') {
+            let remainder = remainder.trim_start();
+            let (raw, remainder) = match remainder.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let quoted = &remainder[1..];
+                    let end = quoted.find(quote).unwrap_or(quoted.len());
+                    (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = remainder
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(remainder.len());
+                    (&remainder[..end], &remainder[end..])
+                }
+            };
+            value = decode_entities(raw);
+            rest = remainder;
+        }
+        attrs.push((attr, value));
+    }
+    (Some(Token::Open(name, attrs)), rest)
+}
+
+fn attribute<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(attr, _)| attr == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Decodes text of HTML document, collapsing its whitespace.
+fn decode_text(text: &str) -> String {
+    collapse_whitespace(&decode_entities(text))
+}
+
+enum Capture {
+    Nothing,
+    FolderName(String),
+    LinkName(Link),
+    Description(usize, usize, String),
+}
+
+#[derive(Default)]
+struct Collector {
+    folders: Vec<Folder>,
+}
+
+impl Collector {
+    /// Adds link to the folder of given path, returning link's location.
+    fn add(&mut self, path: &[String], link: Link) -> (usize, usize) {
+        let name = match path.is_empty() {
+            true => UNSORTED_FOLDER.to_string(),
+            false => path.join(FOLDER_SEPARATOR),
+        };
+        let idx = match self.folders.iter().position(|f| f.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.folders.push(Folder {
+                    name,
+                    links: Vec::new(),
+                    nested: path.len() > 1,
+                });
+                self.folders.len() - 1
+            }
+        };
+        self.folders[idx].links.push(link);
+        (idx, self.folders[idx].links.len() - 1)
+    }
+}
+
+/// Parses Netscape bookmark file into a list of folders, in order of their appearance.
+/// Folders which contain no links (other than in their sub-folders) are omitted.
+pub fn parse(html: &str) -> Vec<Folder> {
+    let mut collector = Collector::default();
+    let mut lists: Vec<Vec<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut capture = Capture::Nothing;
+
+    // location of recently added link, to be described by <DD> which may follow it
+    let mut last_link: Option<(usize, usize)> = None;
+
+    for token in tokenize(html) {
+        // description lasts until any structural tag shows up
+        if let (Capture::Description(..), Token::Open(..) | Token::Close(..)) = (&capture, &token) {
+            if let Capture::Description(folder, link, text) =
+                std::mem::replace(&mut capture, Capture::Nothing)
+            {
+                let text = decode_text(&text);
+                if !text.is_empty() {
+                    collector.folders[folder].links[link].description = Some(text);
+                }
+            }
+        }
+
+        match token {
+            Token::Text(text) => match &mut capture {
+                Capture::FolderName(name) => name.push_str(text),
+                Capture::LinkName(link) => link.name.push_str(text),
+                Capture::Description(_, _, description) => description.push_str(text),
+                Capture::Nothing => {}
+            },
+            Token::Open(tag, attrs) => match tag.as_str() {
+                "h3" => {
+                    last_link = None;
+                    capture = Capture::FolderName(String::new())
+                }
+                "a" => {
+                    capture = Capture::LinkName(Link {
+                        name: String::new(),
+                        url: attribute(&attrs, "href")
+                            .unwrap_or_default()
+                            .trim()
+                            .to_string(),
+                        description: None,
+                        icon: None,
+                        visible: true,
+                        added_at: attribute(&attrs, "add_date").and_then(parse_timestamp),
+                    })
+                }
+                "dd" => {
+                    if let Some((folder, link)) = last_link.take() {
+                        capture = Capture::Description(folder, link, String::new());
+                    }
+                }
+                "dl" => {
+                    let mut path = lists.last().cloned().unwrap_or_default();
+                    path.extend(pending_folder.take());
+                    lists.push(path);
+                }
+                _ => {}
+            },
+            Token::Close(tag) => match tag.as_str() {
+                "h3" => {
+                    if let Capture::FolderName(name) =
+                        std::mem::replace(&mut capture, Capture::Nothing)
+                    {
+                        let name = decode_text(&name);
+                        pending_folder = Some(if name.is_empty() {
+                            UNSORTED_FOLDER.to_string()
+                        } else {
+                            name
+                        });
+                    }
+                }
+                "a" => {
+                    if let Capture::LinkName(mut link) =
+                        std::mem::replace(&mut capture, Capture::Nothing)
+                    {
+                        link.name = decode_text(&link.name);
+                        let path = lists.last().map(Vec::as_slice).unwrap_or_default();
+                        last_link = Some(collector.add(path, link));
+                    }
+                }
+                "dl" => {
+                    lists.pop();
+                }
+                _ => {}
+            },
+        }
+    }
+    collector.folders
+}
+
+fn render_node(node: &Node, out: &mut String, depth: usize) {
+    let indent = "    ".repeat(depth);
+    for link in &node.links {
+        let _ = write!(out, "{indent}<DT><A HREF=\"{}\"", escape(&link.url));
+        if let Some(added_at) = link.added_at {
+            let _ = write!(out, " ADD_DATE=\"{}\"", added_at.unix_timestamp());
+        }
+        let _ = writeln!(out, ">{}</A>", escape(&link.name));
+        if let Some(description) = &link.description {
+            let _ = writeln!(out, "{indent}<DD>{}", escape(description));
+        }
+    }
+    for child in &node.children {
+        let _ = writeln!(out, "{indent}<DT><H3>{}</H3>", escape(child.name));
+        let _ = writeln!(out, "{indent}<DL><p>");
+        render_node(child, out, depth + 1);
+        let _ = writeln!(out, "{indent}</DL><p>");
+    }
+}
+/// Renders folders as Netscape bookmark file. Nested folders' names are split by " / " and
+/// rendered as nested folders, links of [`UNSORTED_FOLDER`] are put at the top level.
+pub fn render(folders: &[Folder]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <!-- This is an automatically generated file.\n     \
+         It will be read and overwritten.\n     \
+         DO NOT EDIT! -->\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+    render_node(&Node::tree(folders), &mut out, 1);
+    out.push_str("</DL><p>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    const BOOKMARKS: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><A HREF="https://example.com" ADD_DATE="1583869200">Example &amp; co</A>
+    <DT><H3 ADD_DATE="1583869200">Work</H3>
+    <DL><p>
+        <DT><A HREF="https://grafana.local" ADD_DATE="1583869200000" ICON="data:image/png;base64,AA">Grafana</A>
+        <DD>Production
+            dashboards &lt;EU&gt;
+        <DT><H3>Monitoring</H3>
+        <DL><p>
+            <DT><a href=https://prometheus.local>Prometheus</a>
+        </DL><p>
+        <DT><H3>Empty</H3>
+        <DL><p>
+        </DL><p>
+    </DL><p>
+</DL><p>
+"#;
+
+    #[test]
+    fn parses_nested_folders() {
+        let folders = parse(BOOKMARKS);
+
+        let names: Vec<_> = folders
+            .iter()
+            .map(|f| (f.name.as_str(), f.nested, f.links.len()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (UNSORTED_FOLDER, false, 1),
+                ("Work", false, 1),
+                ("Work / Monitoring", true, 1)
+            ]
+        );
+        assert_eq!(folders[0].links[0].name, "Example & co");
+        assert_eq!(folders[2].links[0].url, "https://prometheus.local");
+
+        let grafana = &folders[1].links[0];
+        assert_eq!(
+            grafana.description.as_deref(),
+            Some("Production dashboards <EU>")
+        );
+        // milliseconds since epoch
+        assert_eq!(
+            grafana.added_at,
+            Some(OffsetDateTime::from_unix_timestamp(1583869200).unwrap())
+        );
+        assert_eq!(grafana.icon, None);
+    }
+
+    #[test]
+    fn tolerates_broken_markup() {
+        let folders = parse(
+            "<DT><A HREF='https://a.com'>A</A><DD>about <b>A<DT><A HREF=\"https://b.com\">B < C",
+        );
+
+        assert_eq!(folders.len(), 1);
+        let links = &folders[0].links;
+        assert_eq!(links[0].description.as_deref(), Some("about"));
+        assert_eq!(
+            (links[0].name.as_str(), links[0].url.as_str()),
+            ("A", "https://a.com")
+        );
+        assert_eq!(links.len(), 1);
+        assert!(parse("").is_empty());
+    }
+
+    #[test]
+    fn round_trips() {
+        let folders = parse(BOOKMARKS);
+        let rendered = render(&folders);
+
+        assert_eq!(parse(&rendered), folders);
+    }
+
+    #[test]
+    fn keeps_separator_in_flat_folder_names() {
+        let folders = vec![Folder {
+            name: "A / B".to_string(),
+            links: vec![Link {
+                name: "Example".to_string(),
+                url: "https://example.com".to_string(),
+                description: None,
+                icon: None,
+                visible: true,
+                added_at: None,
+            }],
+            nested: false,
+        }];
+        let rendered = render(&folders);
+
+        assert!(rendered.contains("<DT><H3>A / B</H3>"));
+        // it's only a single folder once read back, though named like a nested one
+        assert_eq!(parse(&rendered)[0].name, "A / B");
+    }
+}
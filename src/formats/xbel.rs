@@ -0,0 +1,270 @@
+//! XML Bookmark Exchange Language, used by Floccus and many other bookmark sync tools:
+//!
+//! ```xml
+//! <xbel version="1.0">
+//!   <folder>
+//!     <title>Work</title>
+//!     <bookmark href="https://grafana.local" added="2020-03-10T20:00:00Z">
+//!       <title>Grafana</title>
+//!       <desc>Production dashboards</desc>
+//!     </bookmark>
+//!   </folder>
+//! </xbel>
+//! ```
+//!
+//! Similar to Netscape bookmark file, nested folders are flattened into a single level,
+//! with names joined by " / ", and get nested again when rendered. Separators, aliases
+//! and metadata are ignored.
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::fmt::Write;
+use time::format_description::well_known::Rfc3339;
+
+use super::{
+    collapse_whitespace, escape, parse_date, Folder, Link, Node, FOLDER_SEPARATOR, UNSORTED_FOLDER,
+};
+
+/// Creates (yet unnamed) link out of `<bookmark>` element's attributes.
+fn bookmark_link(element: &BytesStart) -> anyhow::Result<Link> {
+    let url = match element.try_get_attribute("href")? {
+        Some(href) => href.unescape_value()?.trim().to_string(),
+        None => String::new(),
+    };
+    let added_at = match element.try_get_attribute("added")? {
+        Some(added) => parse_date(&added.unescape_value()?),
+        None => None,
+    };
+    Ok(Link {
+        name: String::new(),
+        url,
+        description: None,
+        icon: None,
+        visible: true,
+        added_at,
+    })
+}
+
+fn add(folders: &mut Vec<Folder>, path: &[String], link: Link) {
+    let named: Vec<&str> = path
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !name.is_empty())
+        .collect();
+    let name = if named.is_empty() {
+        UNSORTED_FOLDER.to_string()
+    } else {
+        named.join(FOLDER_SEPARATOR)
+    };
+    match folders.iter_mut().find(|f| f.name == name) {
+        Some(folder) => folder.links.push(link),
+        None => folders.push(Folder {
+            name,
+            links: vec![link],
+            nested: named.len() > 1,
+        }),
+    }
+}
+
+/// Parses XBEL document into a list of folders, in order of their appearance. Bookmarks
+/// outside of any folder land in [`UNSORTED_FOLDER`].
+pub fn parse(xml: &str) -> anyhow::Result<Vec<Folder>> {
+    let mut reader = Reader::from_str(xml);
+    let mut folders = Vec::new();
+
+    // names of currently open folders and elements
+    let mut path: Vec<String> = Vec::new();
+    let mut elements: Vec<Vec<u8>> = Vec::new();
+    let mut bookmark: Option<Link> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                match e.name().as_ref() {
+                    b"folder" => path.push(String::new()),
+                    b"bookmark" => bookmark = Some(bookmark_link(&e)?),
+                    _ => {}
+                }
+                elements.push(e.name().as_ref().to_vec());
+            }
+            Event::Empty(e) if e.name().as_ref() == b"bookmark" => {
+                add(&mut folders, &path, bookmark_link(&e)?);
+            }
+            Event::Text(text) => {
+                let text = text.unescape()?;
+                push_text(&elements, &mut path, bookmark.as_mut(), &text);
+            }
+            Event::CData(text) => {
+                let text = String::from_utf8_lossy(&text).into_owned();
+                push_text(&elements, &mut path, bookmark.as_mut(), &text);
+            }
+            Event::End(e) => {
+                elements.pop();
+                match e.name().as_ref() {
+                    b"folder" => {
+                        path.pop();
+                    }
+                    b"bookmark" => {
+                        if let Some(mut link) = bookmark.take() {
+                            link.name = collapse_whitespace(&link.name);
+                            link.description = link
+                                .description
+                                .map(|d| collapse_whitespace(&d))
+                                .filter(|d| !d.is_empty());
+                            add(&mut folders, &path, link);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(folders)
+}
+
+/// Appends text to title or description of element it belongs to.
+fn push_text(elements: &[Vec<u8>], path: &mut [String], bookmark: Option<&mut Link>, text: &str) {
+    let [.., parent, element] = elements else {
+        return;
+    };
+    match (parent.as_slice(), element.as_slice(), bookmark) {
+        (b"bookmark", b"title", Some(link)) => link.name.push_str(text),
+        (b"bookmark", b"desc", Some(link)) => link
+            .description
+            .get_or_insert_with(String::new)
+            .push_str(text),
+        (b"folder", b"title", _) => {
+            if let Some(name) = path.last_mut() {
+                name.push_str(&collapse_whitespace(text));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn render_node(node: &Node, out: &mut String, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for link in &node.links {
+        let _ = write!(out, "{indent}<bookmark href=\"{}\"", escape(&link.url));
+        if let Some(added) = link.added_at.and_then(|a| a.format(&Rfc3339).ok()) {
+            let _ = write!(out, " added=\"{added}\"");
+        }
+        let _ = writeln!(out, ">");
+        let _ = writeln!(out, "{indent}  <title>{}</title>", escape(&link.name));
+        if let Some(description) = &link.description {
+            let _ = writeln!(out, "{indent}  <desc>{}</desc>", escape(description));
+        }
+        let _ = writeln!(out, "{indent}</bookmark>");
+    }
+    for child in &node.children {
+        let _ = writeln!(out, "{indent}<folder>");
+        let _ = writeln!(out, "{indent}  <title>{}</title>", escape(child.name));
+        render_node(child, out, depth + 1);
+        let _ = writeln!(out, "{indent}</folder>");
+    }
+}
+
+/// Renders folders as XBEL document. Nested folders' names are split by " / " and rendered as nested
+/// folders, links of [`UNSORTED_FOLDER`] are put at the top level.
+pub fn render(folders: &[Folder]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE xbel PUBLIC \"+//IDN python.org//DTD XML Bookmark Exchange Language 1.0//EN//XML\" \
+         \"http://pyxml.sourceforge.net/topics/dtds/xbel.dtd\">\n\
+         <xbel version=\"1.0\">\n",
+    );
+    render_node(&Node::tree(folders), &mut out, 1);
+    out.push_str("</xbel>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    const XBEL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE xbel PUBLIC "+//IDN python.org//DTD XML Bookmark Exchange Language 1.0//EN//XML" "http://pyxml.sourceforge.net/topics/dtds/xbel.dtd">
+<xbel version="1.0">
+  <bookmark href="https://example.com"><title>Example &amp; co</title></bookmark>
+  <folder>
+    <title>Work</title>
+    <bookmark href="https://grafana.local" added="2020-03-10T20:00:00Z">
+      <title>Grafana</title>
+      <desc>Production
+        dashboards</desc>
+    </bookmark>
+    <separator/>
+    <folder>
+      <title>Monitoring</title>
+      <bookmark href="https://prometheus.local" added="1583870400000"><title>Prometheus</title></bookmark>
+    </folder>
+  </folder>
+</xbel>
+"#;
+
+    #[test]
+    fn parses_nested_folders() {
+        let folders = parse(XBEL).unwrap();
+
+        let names: Vec<_> = folders
+            .iter()
+            .map(|f| (f.name.as_str(), f.nested))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (UNSORTED_FOLDER, false),
+                ("Work", false),
+                ("Work / Monitoring", true)
+            ]
+        );
+        assert_eq!(folders[0].links[0].name, "Example & co");
+
+        let grafana = &folders[1].links[0];
+        assert_eq!(
+            grafana.description.as_deref(),
+            Some("Production dashboards")
+        );
+        assert_eq!(
+            grafana.added_at,
+            Some(OffsetDateTime::from_unix_timestamp(1583870400).unwrap())
+        );
+        // milliseconds since epoch
+        assert_eq!(
+            folders[2].links[0].added_at,
+            Some(OffsetDateTime::from_unix_timestamp(1583870400).unwrap())
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let folders = parse(XBEL).unwrap();
+        let rendered = render(&folders);
+
+        assert_eq!(parse(&rendered).unwrap(), folders);
+    }
+
+    #[test]
+    fn keeps_separator_in_flat_folder_names() {
+        let folders = vec![Folder {
+            name: "A / B".to_string(),
+            links: vec![Link {
+                name: "Example".to_string(),
+                url: "https://example.com".to_string(),
+                description: None,
+                icon: None,
+                visible: true,
+                added_at: None,
+            }],
+            nested: false,
+        }];
+
+        assert_eq!(parse(&render(&folders)).unwrap(), folders);
+    }
+}
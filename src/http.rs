@@ -0,0 +1,440 @@
+//! Outbound HTTP requests made by trufel on behalf of its users.
+//!
+//! URLs provided by users are fetched by the server (link checks, favicons, page metadata),
+//! so all such requests go through [`Outbound`], guarding them against reaching addresses
+//! they shouldn't (cloud metadata endpoints, internal services):
+//! - every address a host resolves to is checked against allowed and denied CIDR ranges,
+//!   and connection is made only to the checked addresses, which defeats DNS rebinding
+//! - redirects are followed one by one, each of them checked the same way
+//! - requests are time limited, responses size limited and only a few requests to the same
+//!   host are made at once
+//!
+//! It's configured with environment variables:
+//! - `OUTBOUND_DENY` - comma separated CIDR ranges denied in addition to the default ones
+//!   (loopback, link-local, cloud metadata, unspecified, broadcast and multicast addresses,
+//!   along with NAT64 and 6to4 addresses, which might embed any of them)
+//! - `OUTBOUND_ALLOW` - comma separated CIDR ranges allowed even if they're denied, like
+//!   `127.0.0.1` for services running next to trufel. `OUTBOUND_DENY=0.0.0.0/0,::/0` along
+//!   with allowed ranges makes an allowlist
+//! - `OUTBOUND_TIMEOUT` - seconds a request may take (default: 10)
+//! - `OUTBOUND_MAX_REDIRECTS` - maximal number of followed redirects (default: 10)
+//! - `OUTBOUND_HOST_CONCURRENCY` - maximal number of concurrent requests to a host (default: 4)
+
+use anyhow::{anyhow, bail};
+use hyper::client::connect::dns::Name;
+use ipnet::IpNet;
+use lazy_static::lazy_static;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    header, Method, StatusCode, Url,
+};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// User agent trufel introduces itself with.
+pub const USER_AGENT: &str = concat!("trufel/", env!("CARGO_PKG_VERSION"));
+
+/// Ranges denied by default - loopback (trufel itself and services next to it), link-local
+/// (which cloud metadata endpoints live in), other well-known metadata addresses and
+/// addresses no request should be sent to. NAT64 and 6to4 ranges are denied as a whole,
+/// as they translate to any IPv4 address, including the denied ones.
+const DEFAULT_DENY: &[&str] = &[
+    "0.0.0.0/8",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "100.100.100.200/32",
+    "224.0.0.0/4",
+    "255.255.255.255/32",
+    "::/128",
+    "::1/128",
+    "64:ff9b::/96",
+    "2002::/16",
+    "fe80::/10",
+    "fd00:ec2::254/128",
+    "ff00::/8",
+];
+
+lazy_static! {
+    static ref OUTBOUND: Outbound =
+        Outbound::new(OutboundConfig::from_env().expect("Invalid outbound HTTP configuration"));
+}
+
+/// Shared outbound HTTP component, configured from environment.
+pub fn outbound() -> &'static Outbound {
+    &OUTBOUND
+}
+
+/// Validates outbound configuration eagerly, so that the invalid one fails on startup.
+pub fn init_outbound() {
+    lazy_static::initialize(&OUTBOUND);
+}
+
+/// Request refused because of the address it would be sent to.
+#[derive(thiserror::Error, Debug)]
+#[error("{0} is not allowed to be requested")]
+pub struct Blocked(String);
+
+/// Allowed and denied address ranges.
+#[derive(Debug, Clone)]
+pub struct AddressPolicy {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl Default for AddressPolicy {
+    fn default() -> Self {
+        AddressPolicy {
+            allow: Vec::new(),
+            deny: DEFAULT_DENY
+                .iter()
+                .map(|net| net.parse().expect("Invalid default CIDR"))
+                .collect(),
+        }
+    }
+}
+
+fn parse_nets(nets: &str) -> anyhow::Result<Vec<IpNet>> {
+    nets.split(',')
+        .map(str::trim)
+        .filter(|net| !net.is_empty())
+        .map(|net| {
+            net.parse::<IpNet>()
+                .or_else(|_| net.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|e| anyhow!("{net} is not a valid CIDR range ({e})"))
+        })
+        .collect()
+}
+
+impl AddressPolicy {
+    /// Adds comma separated ranges to the allowed and denied ones.
+    pub fn with(mut self, allow: &str, deny: &str) -> anyhow::Result<Self> {
+        self.allow.extend(parse_nets(allow)?);
+        self.deny.extend(parse_nets(deny)?);
+        Ok(self)
+    }
+
+    /// Checks whether requests might be sent to given address. Allowed ranges take
+    /// precedence over the denied ones.
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        // IPv4-mapped IPv6 addresses are checked as the IPv4 ones they are
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        self.allow.iter().any(|net| net.contains(&ip))
+            || !self.deny.iter().any(|net| net.contains(&ip))
+    }
+
+    fn check(&self, ip: IpAddr) -> Result<(), Blocked> {
+        match self.permits(ip) {
+            true => Ok(()),
+            false => Err(Blocked(ip.to_string())),
+        }
+    }
+}
+
+/// Resolver handing over to HTTP client only addresses permitted by policy. Client
+/// connects to the resolved addresses, so a host can't resolve to a permitted address
+/// when checked and to a denied one when connected to.
+struct GuardedResolver {
+    policy: Arc<AddressPolicy>,
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let resolved: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            let permitted: Vec<SocketAddr> = resolved
+                .iter()
+                .filter(|addr| policy.permits(addr.ip()))
+                .copied()
+                .collect();
+            if permitted.is_empty() && !resolved.is_empty() {
+                return Err(Blocked(host.to_string()).into());
+            }
+            let addrs: Addrs = Box::new(permitted.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutboundConfig {
+    pub policy: AddressPolicy,
+    pub timeout: Duration,
+    pub max_redirects: usize,
+    pub host_concurrency: usize,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        OutboundConfig {
+            policy: AddressPolicy::default(),
+            timeout: Duration::from_secs(10),
+            max_redirects: 10,
+            host_concurrency: 4,
+        }
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> anyhow::Result<T> {
+    match std::env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("{name} is not a valid number")),
+        Err(_) => Ok(default),
+    }
+}
+
+impl OutboundConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let default = OutboundConfig::default();
+        Ok(OutboundConfig {
+            policy: default.policy.with(
+                &std::env::var("OUTBOUND_ALLOW").unwrap_or_default(),
+                &std::env::var("OUTBOUND_DENY").unwrap_or_default(),
+            )?,
+            timeout: Duration::from_secs(env_number(
+                "OUTBOUND_TIMEOUT",
+                default.timeout.as_secs(),
+            )?),
+            max_redirects: env_number("OUTBOUND_MAX_REDIRECTS", default.max_redirects)?,
+            host_concurrency: env_number("OUTBOUND_HOST_CONCURRENCY", default.host_concurrency)?
+                .max(1),
+        })
+    }
+}
+
+/// HTTP client guarded by address policy, limiting concurrent requests to the same host.
+/// Redirects are never followed automatically, so that each of them may be inspected.
+pub struct Outbound {
+    client: reqwest::Client,
+    policy: Arc<AddressPolicy>,
+    max_redirects: usize,
+    host_concurrency: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+/// Response fetched with [`Outbound::fetch`].
+#[derive(Debug)]
+pub struct Fetched {
+    /// URL response came from, after following redirects.
+    pub url: Url,
+    pub status: StatusCode,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl Fetched {
+    /// Media type of response, without parameters like charset.
+    pub fn media_type(&self) -> Option<String> {
+        self.content_type
+            .as_deref()
+            .and_then(|ct| ct.split(';').next())
+            .map(|mt| mt.trim().to_lowercase())
+    }
+}
+
+impl Outbound {
+    pub fn new(config: OutboundConfig) -> Self {
+        let policy = Arc::new(config.policy);
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .redirect(reqwest::redirect::Policy::none())
+            // proxy would resolve hosts on its own
+            .no_proxy()
+            .dns_resolver(Arc::new(GuardedResolver {
+                policy: policy.clone(),
+            }))
+            .connect_timeout(config.timeout)
+            .timeout(config.timeout)
+            .build()
+            .expect("Couldn't create HTTP client");
+
+        Outbound {
+            client,
+            policy,
+            max_redirects: config.max_redirects,
+            host_concurrency: config.host_concurrency,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects
+    }
+
+    /// Checks URL before it gets requested. Hosts given by IP addresses are never resolved,
+    /// so they're checked right away.
+    fn check_url(&self, url: &Url) -> anyhow::Result<()> {
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("{url} is not an http(s) URL");
+        }
+        let Some(host) = url.host_str() else {
+            bail!("{url} has no host");
+        };
+        if let Ok(ip) = host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            self.policy.check(ip)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for a free slot of requests to URL's host.
+    async fn host_permit(&self, url: &Url) -> OwnedSemaphorePermit {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let semaphore = {
+            let mut hosts = self.hosts.lock().expect("Poisoned hosts lock");
+            // semaphores of hosts with no pending requests aren't needed anymore
+            hosts.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+            hosts
+                .entry(host)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.host_concurrency)))
+                .clone()
+        };
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("Host semaphore closed")
+    }
+
+    /// Sends a single request, without following redirects. Response body is expected
+    /// to be ignored, as the slot of host's requests is freed once headers are received.
+    pub async fn send(&self, method: Method, url: &Url) -> anyhow::Result<reqwest::Response> {
+        self.check_url(url)?;
+        let _permit = self.host_permit(url).await;
+        Ok(self.client.request(method, url.clone()).send().await?)
+    }
+
+    /// Fetches given URL with `GET` request, following redirects. Fetching fails when
+    /// response body exceeds `max_size` bytes.
+    pub async fn fetch(&self, url: Url, max_size: usize) -> anyhow::Result<Fetched> {
+        let mut url = url;
+        for _ in 0..=self.max_redirects {
+            self.check_url(&url)?;
+            let _permit = self.host_permit(&url).await;
+            let mut response = self.client.get(url.clone()).send().await?;
+            let status = response.status();
+
+            if status.is_redirection() {
+                if let Some(location) = response
+                    .headers()
+                    .get(header::LOCATION)
+                    .and_then(|l| l.to_str().ok())
+                {
+                    url = url.join(location)?;
+                    continue;
+                }
+            }
+            if response
+                .content_length()
+                .is_some_and(|len| len > max_size as u64)
+            {
+                bail!("{url} response exceeds {max_size} bytes");
+            }
+            let content_type = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|ct| ct.to_str().ok())
+                .map(str::to_string);
+
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await? {
+                if body.len() + chunk.len() > max_size {
+                    bail!("{url} response exceeds {max_size} bytes");
+                }
+                body.extend_from_slice(&chunk);
+            }
+            return Ok(Fetched {
+                url,
+                status,
+                content_type,
+                body,
+            });
+        }
+        bail!("{url} redirects more than {} times", self.max_redirects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn denies_internal_addresses_by_default() {
+        let policy = AddressPolicy::default();
+
+        for denied in [
+            "127.0.0.1",
+            "127.1.2.3",
+            "::1",
+            "169.254.169.254",
+            "100.100.100.200",
+            "fd00:ec2::254",
+            "fe80::1",
+            "0.0.0.0",
+            "::",
+            "224.0.0.1",
+            "255.255.255.255",
+            "ff02::1",
+            // IPv4-mapped, NAT64 and 6to4 embeddings of metadata endpoint
+            "::ffff:169.254.169.254",
+            "64:ff9b::a9fe:a9fe",
+            "2002:a9fe:a9fe::1",
+        ] {
+            assert!(!policy.permits(ip(denied)), "{denied} should be denied");
+        }
+        for permitted in [
+            "93.184.216.34",
+            "10.0.0.1",
+            "192.168.1.1",
+            "2606:4700::1111",
+        ] {
+            assert!(
+                policy.permits(ip(permitted)),
+                "{permitted} should be permitted"
+            );
+        }
+    }
+
+    #[test]
+    fn allowed_ranges_take_precedence() {
+        let policy = AddressPolicy::default()
+            .with("127.0.0.1, 10.1.0.0/16", "10.0.0.0/8")
+            .unwrap();
+
+        assert!(policy.permits(ip("127.0.0.1")));
+        assert!(!policy.permits(ip("127.0.0.2")));
+        assert!(policy.permits(ip("10.1.2.3")));
+        assert!(!policy.permits(ip("10.2.0.1")));
+        assert!(!policy.permits(ip("::ffff:10.2.0.1")));
+    }
+
+    #[test]
+    fn makes_allowlist_out_of_denied_everything() {
+        let policy = AddressPolicy::default()
+            .with("192.168.1.0/24", "0.0.0.0/0,::/0")
+            .unwrap();
+
+        assert!(policy.permits(ip("192.168.1.10")));
+        assert!(!policy.permits(ip("93.184.216.34")));
+        assert!(!policy.permits(ip("2606:4700::1111")));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(AddressPolicy::default().with("localhost", "").is_err());
+        assert!(AddressPolicy::default().with("", "10.0.0.0/33").is_err());
+    }
+}
@@ -0,0 +1,244 @@
+use serde::Serialize;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::{
+    errors::InternalError,
+    formats::{App, Dashboard, Folder, Link, APPLICATIONS_FOLDER},
+    models::{
+        application::{self, ApplicationDetails},
+        bookmark::{self, BookmarkDetails},
+        category,
+        user::User,
+    },
+    urls,
+};
+
+#[derive(Serialize, Debug)]
+pub struct ImportedEntry {
+    pub category: String,
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SkippedEntry {
+    pub category: String,
+    pub name: String,
+    pub url: String,
+    pub reason: String,
+}
+
+/// Summary of import - what has been created and what (and why) has been left out.
+#[derive(Serialize, Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub applications_imported: usize,
+    pub categories_created: Vec<String>,
+    pub duplicates: Vec<ImportedEntry>,
+    pub skipped: Vec<SkippedEntry>,
+}
+
+impl ImportReport {
+    /// Validates and canonicalizes URL of imported link. Invalid links are reported as skipped.
+    fn validate(&mut self, category: &str, link: &Link) -> Option<String> {
+        match urls::canonical_url(&link.url) {
+            Ok(url) => Some(url.to_string()),
+            Err(e) => {
+                self.skipped.push(SkippedEntry {
+                    category: category.to_string(),
+                    name: link.name.clone(),
+                    url: link.url.clone(),
+                    reason: e.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn duplicate(&mut self, category: &str, link: Link) {
+        self.duplicates.push(ImportedEntry {
+            category: category.to_string(),
+            name: link.name,
+            url: link.url,
+        });
+    }
+}
+
+/// Returns user's category of given name. Category gets created if it doesn't exist yet,
+/// marked as nested if it's named by a path of nested folders.
+async fn ensure_category(
+    conn: &mut SqliteConnection,
+    user: &User,
+    name: &str,
+    nested: bool,
+    report: &mut ImportReport,
+) -> anyhow::Result<Uuid> {
+    if let Some(category) = category::find_category_by_name_in(conn, user, name).await? {
+        return Ok(category.id);
+    }
+    let category = category::create_category_in(conn, user, name.to_string(), nested).await?;
+    report.categories_created.push(category.name);
+    Ok(category.id)
+}
+
+fn import_error(e: sqlx::Error) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't import bookmarks");
+    InternalError::BookmarksCreate
+}
+
+/// Link's name, or its URL if no name was provided.
+fn name_or_url(name: String, url: &str) -> String {
+    if name.trim().is_empty() {
+        url.to_string()
+    } else {
+        name
+    }
+}
+
+/// Imports folders of links as user's categories and bookmarks.
+///
+/// Folders are mapped onto categories by their names, missing categories are created.
+/// Links keep their order within a folder and are appended at the end of category. Links
+/// already bookmarked by user (or repeated within imported collection) are reported
+/// as duplicates, invalid ones are skipped. Import is all or nothing - nothing gets
+/// created if any part of it fails.
+pub async fn import_folders(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    folders: Vec<Folder>,
+) -> anyhow::Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await.map_err(import_error)?;
+    import_bookmarks(&mut tx, user, folders, &mut report).await?;
+    tx.commit().await.map_err(import_error)?;
+    Ok(report)
+}
+
+/// Imports complete dashboard - applications first, then folders of bookmarks.
+///
+/// Applications grouped by launcher are assigned to categories of groups' names.
+pub async fn import_dashboard(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    dashboard: Dashboard,
+) -> anyhow::Result<ImportReport> {
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await.map_err(import_error)?;
+    import_applications(&mut tx, user, dashboard.applications, &mut report).await?;
+    import_bookmarks(&mut tx, user, dashboard.folders, &mut report).await?;
+    tx.commit().await.map_err(import_error)?;
+    Ok(report)
+}
+
+async fn import_bookmarks(
+    conn: &mut SqliteConnection,
+    user: &User,
+    folders: Vec<Folder>,
+    report: &mut ImportReport,
+) -> anyhow::Result<()> {
+    let mut known_urls: HashSet<String> = bookmark::fetch_bookmarks_in(conn, user, None)
+        .await?
+        .into_iter()
+        .map(|b| urls::duplicate_key(&b.url))
+        .collect();
+    let mut categories: HashMap<String, Uuid> = HashMap::new();
+
+    for folder in folders {
+        let category = folder.name.trim().to_string();
+
+        for link in folder.links {
+            let Some(url) = report.validate(&category, &link) else {
+                continue;
+            };
+            if !known_urls.insert(urls::duplicate_key(&url)) {
+                report.duplicate(&category, link);
+                continue;
+            }
+            let category_id = match categories.get(&category) {
+                Some(id) => *id,
+                None => {
+                    let id = ensure_category(conn, user, &category, folder.nested, report).await?;
+                    categories.insert(category.clone(), id);
+                    id
+                }
+            };
+            let details = BookmarkDetails {
+                category_id,
+                name: name_or_url(link.name, &url),
+                url,
+                icon: link.icon,
+                // bookmarks have no descriptions, so they're kept as notes
+                notes: link.description,
+                visible: link.visible,
+            };
+            bookmark::create_bookmark_in(conn, user, details, link.added_at).await?;
+            report.imported += 1;
+        }
+    }
+    Ok(())
+}
+
+async fn import_applications(
+    conn: &mut SqliteConnection,
+    user: &User,
+    applications: Vec<App>,
+    report: &mut ImportReport,
+) -> anyhow::Result<()> {
+    let mut known_urls: HashSet<String> = application::fetch_own_applications_in(conn, user)
+        .await?
+        .into_iter()
+        .map(|a| urls::duplicate_key(&a.url))
+        .collect();
+    let mut categories: HashMap<String, Uuid> = HashMap::new();
+
+    for App {
+        link,
+        category,
+        shared,
+    } in applications
+    {
+        let category = category
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        let folder = category.as_deref().unwrap_or(APPLICATIONS_FOLDER);
+
+        let Some(url) = report.validate(folder, &link) else {
+            continue;
+        };
+        if !known_urls.insert(urls::duplicate_key(&url)) {
+            report.duplicate(folder, link);
+            continue;
+        }
+        let category_id = match category {
+            Some(category) => Some(match categories.get(&category) {
+                Some(id) => *id,
+                None => {
+                    let id = ensure_category(conn, user, &category, false, report).await?;
+                    categories.insert(category, id);
+                    id
+                }
+            }),
+            None => None,
+        };
+        let details = ApplicationDetails {
+            category_id,
+            name: name_or_url(link.name, &url),
+            description: link.description,
+            url,
+            icon: link.icon,
+            notes: None,
+            visible: link.visible,
+            // only admins share applications, anyone else imports them as their own
+            shared: shared && user.admin,
+            group_id: None,
+            searchable: false,
+            search_url: None,
+            keyword: None,
+        };
+        application::create_application_in(conn, user, details).await?;
+        report.applications_imported += 1;
+    }
+    Ok(())
+}
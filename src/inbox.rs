@@ -0,0 +1,106 @@
+//! Quick-add of links shared from elsewhere, like by bookmarklets or Web Share Target.
+//! Links are stored as bookmarks of an "Inbox" category (created on demand), named after
+//! the title of their pages unless a title is provided, to be sorted out later.
+
+use reqwest::Url;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::RequestError,
+    metadata,
+    models::{
+        bookmark::{self, Bookmark, BookmarkDetails},
+        category, duplicate,
+        user::User,
+    },
+};
+
+/// Name of category quickly added links are stored into.
+pub const INBOX_CATEGORY: &str = "Inbox";
+
+/// Shared link. Share targets may provide the URL as a part of shared text only.
+#[derive(Deserialize, Debug, Default)]
+pub struct SharedLink {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Outcome of quick-add. Links which are already bookmarked aren't added again.
+pub enum QuickAdded {
+    Created(Bookmark),
+    Existing(Bookmark),
+}
+
+fn non_blank(text: Option<&str>) -> Option<&str> {
+    text.map(str::trim).filter(|text| !text.is_empty())
+}
+
+/// Finds the first web URL within shared text.
+fn find_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .find(|word| Url::parse(word).is_ok_and(|url| matches!(url.scheme(), "http" | "https")))
+}
+
+/// Returns user's inbox category, creating one if there's none yet.
+pub async fn ensure_inbox(pool: &SqlitePool, user: &User) -> anyhow::Result<Uuid> {
+    if let Some(inbox) = category::find_category_by_name(pool, user, INBOX_CATEGORY).await? {
+        return Ok(inbox.id);
+    }
+    Ok(
+        category::create_category(pool, user, INBOX_CATEGORY.to_string())
+            .await?
+            .id,
+    )
+}
+
+/// Title of linked page, or the URL itself if there's no title to be found.
+pub async fn page_title(url: &str) -> String {
+    match metadata::page_metadata(url).await {
+        Ok(metadata) => metadata.title.unwrap_or_else(|| url.to_string()),
+        Err(e) => {
+            tracing::debug!(error = ?e, url, "Couldn't fetch title of linked page");
+            url.to_string()
+        }
+    }
+}
+
+/// Bookmarks shared link into user's inbox. Any text shared along with the URL is kept
+/// as bookmark's notes.
+pub async fn quick_add(
+    pool: &SqlitePool,
+    user: &User,
+    link: SharedLink,
+) -> anyhow::Result<QuickAdded> {
+    let text = non_blank(link.text.as_deref());
+    let url = non_blank(link.url.as_deref())
+        .or_else(|| text.and_then(find_url))
+        .ok_or_else(|| RequestError::Invalid("url", "no URL to add".into()))?
+        .to_string();
+
+    if let Some(existing) = duplicate::find_bookmark_duplicate(pool, user, &url).await? {
+        return Ok(QuickAdded::Existing(existing));
+    }
+
+    let name = match non_blank(link.title.as_deref()) {
+        Some(title) => title.to_string(),
+        None => page_title(&url).await,
+    };
+    let notes = text
+        .map(|text| text.replace(url.as_str(), ""))
+        .filter(|notes| !notes.trim().is_empty());
+
+    let details = BookmarkDetails {
+        category_id: ensure_inbox(pool, user).await?,
+        name,
+        url,
+        icon: None,
+        notes,
+        visible: true,
+    };
+    Ok(QuickAdded::Created(
+        bookmark::create_bookmark(pool, user, details).await?,
+    ))
+}
@@ -0,0 +1,356 @@
+//! Dead-link checker, periodically checking URLs of all bookmarks and applications.
+//!
+//! Each URL is requested with `HEAD` (falling back to `GET` for servers which don't
+//! handle `HEAD` properly) and redirects are followed one by one. Link is considered
+//! broken when it can't be reached at all, or responds with 404, 410 or any server
+//! error. Other client errors (like 401 or 403) are expected from apps hidden behind
+//! authentication and are not reported.
+//!
+//! Checker is configured with environment variables:
+//! - `LINK_CHECK_INTERVAL` - seconds between checking rounds (default: 3600, 0 disables checker)
+//! - `LINK_CHECK_MAX_AGE` - seconds after which link gets checked again (default: 86400)
+
+use futures::{stream, StreamExt};
+use reqwest::{Method, StatusCode, Url};
+use sqlx::SqlitePool;
+use std::{collections::HashSet, time::Duration};
+
+use crate::{
+    http::{self, Blocked, Outbound},
+    models::link_check::{self, LinkStatus},
+};
+
+const DEFAULT_INTERVAL: u64 = 60 * 60;
+const DEFAULT_MAX_AGE: u64 = 24 * 60 * 60;
+
+/// Number of links checked at once.
+const CONCURRENCY: usize = 8;
+
+/// Number of links taken for checking at a time.
+const BATCH_SIZE: u32 = 100;
+
+pub struct LinkChecker<'a> {
+    outbound: &'a Outbound,
+}
+
+impl Default for LinkChecker<'static> {
+    fn default() -> Self {
+        LinkChecker::new(http::outbound())
+    }
+}
+
+fn is_broken(status: StatusCode) -> bool {
+    status == StatusCode::NOT_FOUND || status == StatusCode::GONE || status.is_server_error()
+}
+
+/// Status of link which couldn't be requested. Links to blocked addresses are not
+/// considered broken, as they're never checked.
+fn failed(final_url: Option<String>, e: anyhow::Error) -> LinkStatus {
+    if let Some(blocked) = e.chain().find_map(|cause| cause.downcast_ref::<Blocked>()) {
+        return LinkStatus {
+            final_url,
+            error: Some(blocked.to_string()),
+            ..Default::default()
+        };
+    }
+    let error = match e.downcast::<reqwest::Error>() {
+        Ok(e) => e.without_url().to_string(),
+        Err(e) => e.to_string(),
+    };
+    LinkStatus {
+        final_url,
+        broken: true,
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+impl<'a> LinkChecker<'a> {
+    pub fn new(outbound: &'a Outbound) -> Self {
+        LinkChecker { outbound }
+    }
+
+    /// Requests given URL, retrying with `GET` if `HEAD` fails.
+    async fn request(&self, url: &Url) -> anyhow::Result<reqwest::Response> {
+        match self.outbound.send(Method::HEAD, url).await {
+            Ok(response)
+                if !response.status().is_client_error() && !is_broken(response.status()) =>
+            {
+                Ok(response)
+            }
+            Err(e) if e.chain().any(|cause| cause.is::<Blocked>()) => Err(e),
+            _ => self.outbound.send(Method::GET, url).await,
+        }
+    }
+
+    /// Checks given URL, following its redirects.
+    pub async fn check(&self, url: &str) -> LinkStatus {
+        let mut current = match Url::parse(url) {
+            Ok(url) => url,
+            Err(e) => {
+                return LinkStatus {
+                    broken: true,
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            }
+        };
+        let mut permanent = true;
+
+        for _ in 0..=self.outbound.max_redirects() {
+            let response = match self.request(&current).await {
+                Ok(response) => response,
+                Err(e) => return failed((current.as_str() != url).then(|| current.to_string()), e),
+            };
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|l| l.to_str().ok())
+                .and_then(|l| current.join(l).ok());
+
+            match location {
+                Some(next) if status.is_redirection() => {
+                    permanent &= status == StatusCode::MOVED_PERMANENTLY
+                        || status == StatusCode::PERMANENT_REDIRECT;
+                    current = next;
+                }
+                _ => {
+                    let redirected = current.as_str() != url;
+                    return LinkStatus {
+                        status: Some(status.as_u16()),
+                        final_url: redirected.then(|| current.to_string()),
+                        permanent_redirect: redirected && permanent && status.is_success(),
+                        broken: is_broken(status),
+                        error: None,
+                    };
+                }
+            }
+        }
+        LinkStatus {
+            final_url: Some(current.to_string()),
+            broken: true,
+            error: Some(format!(
+                "more than {} redirects",
+                self.outbound.max_redirects()
+            )),
+            ..Default::default()
+        }
+    }
+}
+
+/// Checks all links which haven't been checked for at least `max_age` seconds.
+/// Returns number of checked links.
+pub async fn check_stale_links(
+    pool: &SqlitePool,
+    checker: &LinkChecker<'_>,
+    max_age: u64,
+) -> anyhow::Result<usize> {
+    let mut checked = HashSet::new();
+    loop {
+        // links checked in this round might already be stale again with a short max age
+        let urls: Vec<String> = link_check::fetch_stale_urls(pool, max_age, BATCH_SIZE)
+            .await?
+            .into_iter()
+            .filter(|url| !checked.contains(url))
+            .collect();
+        if urls.is_empty() {
+            break;
+        }
+        let results: Vec<(String, LinkStatus)> = stream::iter(urls)
+            .map(|url| async move {
+                let status = checker.check(&url).await;
+                (url, status)
+            })
+            .buffer_unordered(CONCURRENCY)
+            .collect()
+            .await;
+
+        for (url, status) in &results {
+            if status.broken {
+                tracing::debug!(url, error = ?status.error, status = ?status.status, "Broken link found");
+            }
+            link_check::store_link_check(pool, url, status).await?;
+        }
+        checked.extend(results.into_iter().map(|(url, _)| url));
+    }
+    Ok(checked.len())
+}
+
+fn env_seconds(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Spawns background task checking stale links periodically.
+pub fn spawn_link_checker(pool: SqlitePool) {
+    let interval = env_seconds("LINK_CHECK_INTERVAL", DEFAULT_INTERVAL);
+    let max_age = env_seconds("LINK_CHECK_MAX_AGE", DEFAULT_MAX_AGE);
+    if interval == 0 {
+        tracing::info!("Link checker disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let checker = LinkChecker::default();
+        let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+        loop {
+            ticker.tick().await;
+            match check_stale_links(&pool, &checker, max_age).await {
+                Ok(checked) => tracing::debug!(checked, "Links checked"),
+                Err(e) => tracing::error!(error = ?e, "Couldn't check links"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    // stand-in server speaks newer `http` than reqwest does
+    use axum::{
+        http::{header, StatusCode as Status},
+        response::IntoResponse,
+        routing::get,
+        Router,
+    };
+
+    use super::*;
+    use crate::http::{AddressPolicy, OutboundConfig};
+
+    fn redirect(status: Status, location: &'static str) -> impl IntoResponse {
+        (status, [(header::LOCATION, location)])
+    }
+
+    /// Serves a few pages on a local port, returning server's base URL.
+    async fn serve() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/missing", get(|| async { Status::NOT_FOUND }))
+            .route("/private", get(|| async { Status::FORBIDDEN }))
+            .route(
+                "/moved",
+                get(|| async { redirect(Status::MOVED_PERMANENTLY, "/moved-again") }),
+            )
+            .route(
+                "/moved-again",
+                get(|| async { redirect(Status::PERMANENT_REDIRECT, "/ok") }),
+            )
+            .route(
+                "/temporary",
+                get(|| async { redirect(Status::FOUND, "/moved-again") }),
+            )
+            .route("/loop", get(|| async { redirect(Status::FOUND, "/loop") }))
+            .route(
+                "/no-head",
+                get(|| async { "ok" }).head(|| async { Status::METHOD_NOT_ALLOWED }),
+            )
+            .route(
+                "/broken-head",
+                get(|| async { "ok" }).head(|| async { Status::INTERNAL_SERVER_ERROR }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn outbound() -> Outbound {
+        Outbound::new(OutboundConfig {
+            policy: AddressPolicy::default().with("127.0.0.1", "").unwrap(),
+            max_redirects: 3,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn reports_reachable_link() {
+        let base = serve().await;
+        let outbound = outbound();
+        let checker = LinkChecker::new(&outbound);
+
+        let status = checker.check(&format!("{base}/ok")).await;
+        assert_eq!(
+            status,
+            LinkStatus {
+                status: Some(200),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_missing_link_as_broken() {
+        let base = serve().await;
+        let outbound = outbound();
+        let checker = LinkChecker::new(&outbound);
+
+        let status = checker.check(&format!("{base}/missing")).await;
+        assert_eq!(status.status, Some(404));
+        assert!(status.broken);
+
+        let status = checker.check(&format!("{base}/private")).await;
+        assert_eq!(status.status, Some(403));
+        assert!(!status.broken);
+    }
+
+    #[tokio::test]
+    async fn follows_permanent_redirects() {
+        let base = serve().await;
+        let outbound = outbound();
+        let checker = LinkChecker::new(&outbound);
+
+        let status = checker.check(&format!("{base}/moved")).await;
+        assert_eq!(
+            status,
+            LinkStatus {
+                status: Some(200),
+                final_url: Some(format!("{base}/ok")),
+                permanent_redirect: true,
+                ..Default::default()
+            }
+        );
+
+        let status = checker.check(&format!("{base}/temporary")).await;
+        assert_eq!(status.final_url, Some(format!("{base}/ok")));
+        assert!(!status.permanent_redirect);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_redirect_loop() {
+        let base = serve().await;
+        let outbound = outbound();
+        let checker = LinkChecker::new(&outbound);
+
+        let status = checker.check(&format!("{base}/loop")).await;
+        assert!(status.broken);
+        assert_eq!(status.error.as_deref(), Some("more than 3 redirects"));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_get() {
+        let base = serve().await;
+        let outbound = outbound();
+        let checker = LinkChecker::new(&outbound);
+
+        for path in ["no-head", "broken-head"] {
+            let status = checker.check(&format!("{base}/{path}")).await;
+            assert_eq!(status.status, Some(200), "{path}");
+            assert!(!status.broken, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_blocked_addresses() {
+        let base = serve().await;
+        let outbound = Outbound::new(OutboundConfig::default());
+        let checker = LinkChecker::new(&outbound);
+
+        let status = checker.check(&format!("{base}/missing")).await;
+        assert!(!status.broken);
+        assert_eq!(status.status, None);
+        assert!(status.error.is_some());
+    }
+}
@@ -1,19 +1,35 @@
 #![feature(str_split_remainder)]
 
+mod archiver;
+mod backup;
+mod cli;
 mod db;
 mod errors;
+mod exporter;
 mod extractors;
+mod favicons;
+mod formats;
+mod http;
+mod importer;
+mod inbox;
 mod jwt;
+mod links;
+mod metadata;
 mod middlewares;
 mod models;
+mod notes;
+mod opensearch;
 mod routes;
 mod sentry;
 mod telemetry;
+mod trash;
+mod urls;
 
 use axum::{
+    extract::DefaultBodyLimit,
     http::{header, Method},
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Extension, Router,
 };
 use semver::Version;
@@ -25,9 +41,31 @@ use tower_http::{
 };
 use tracing_log::LogTracer;
 
+use routes::applications;
+use routes::archives;
+use routes::bookmarks;
+use routes::bulk;
 use routes::categories;
+use routes::duplicates;
+use routes::exports;
+use routes::groups;
+use routes::icons;
+use routes::imports;
+use routes::inbox as quick_add;
+use routes::linkding;
+use routes::links as link_checks;
+use routes::metadata as page_metadata;
+use routes::opensearch as open_search;
 use routes::pusher;
+use routes::search;
+use routes::tags;
+use routes::tokens;
+use routes::trash as trashed;
 use routes::users;
+use routes::visits;
+
+/// Maximal size of uploaded files with imported bookmarks.
+const IMPORT_BODY_LIMIT: usize = 16 * 1024 * 1024;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -37,9 +75,6 @@ async fn main() -> anyhow::Result<()> {
     telemetry::init_telemetry()?;
     let _sentry = sentry::init_sentry();
 
-    // JWT and OIDC integration
-    let jwks = jwt::fetch_jwks().await?;
-
     // SQLite connection pre-initialized with a migration scripts if needed
     let pool = db::init_pool()
         .await
@@ -47,12 +82,202 @@ async fn main() -> anyhow::Result<()> {
 
     db::migrate(&pool, Version::parse(env!("CARGO_PKG_VERSION")).unwrap()).await?;
 
+    // Command line tools run instead of server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::run(&pool, &args).await? {
+        return Ok(());
+    }
+
+    // JWT and OIDC integration
+    let jwks = jwt::fetch_jwks().await?;
+
+    // Dead-link checker running in background
+    http::init_outbound();
+    links::spawn_link_checker(pool.clone());
+
+    // Expired trash purged in background
+    trash::spawn_trash_purger(pool.clone());
+
     let serve_dir = ServeDir::new("dist/assets");
     let app = Router::new()
         .route("/@me", get(users::user_identity))
         .route("/user", post(users::user_update))
         .route("/categories", get(categories::categories))
         .route("/categories", post(categories::add_category))
+        .route("/categories/:id", delete(categories::remove_category))
+        .route(
+            "/applications",
+            get(applications::applications).post(applications::add_application),
+        )
+        .route("/applications/bulk", post(bulk::bulk_applications))
+        .route(
+            "/applications/:id",
+            get(applications::get_application)
+                .put(applications::update_application)
+                .delete(applications::delete_application),
+        )
+        .route(
+            "/applications/:id/merge",
+            post(duplicates::merge_applications),
+        )
+        .route(
+            "/applications/:id/metadata",
+            post(page_metadata::refresh_application_metadata),
+        )
+        .route(
+            "/applications/:id/rewrite",
+            post(link_checks::rewrite_application_url),
+        )
+        .route(
+            "/applications/:id/overlay",
+            put(applications::set_application_overlay)
+                .delete(applications::reset_application_overlay),
+        )
+        .route("/applications/:id/tags", get(tags::application_tags))
+        .route(
+            "/applications/:id/tags/:name",
+            put(tags::tag_application).delete(tags::untag_application),
+        )
+        .route(
+            "/bookmarks",
+            get(bookmarks::bookmarks).post(bookmarks::add_bookmark),
+        )
+        .route("/bookmarks/bulk", post(bulk::bulk_bookmarks))
+        .route(
+            "/bookmarks/:id",
+            get(bookmarks::get_bookmark)
+                .put(bookmarks::update_bookmark)
+                .delete(bookmarks::delete_bookmark),
+        )
+        .route(
+            "/bookmarks/:id/visibility",
+            put(bookmarks::update_bookmark_visibility),
+        )
+        .route(
+            "/bookmarks/:id/archive",
+            get(archives::get_archive)
+                .post(archives::archive_bookmark)
+                .delete(archives::delete_archive),
+        )
+        .route("/bookmarks/:id/merge", post(duplicates::merge_bookmarks))
+        .route(
+            "/bookmarks/:id/metadata",
+            post(page_metadata::refresh_bookmark_metadata),
+        )
+        .route(
+            "/bookmarks/:id/rewrite",
+            post(link_checks::rewrite_bookmark_url),
+        )
+        .route("/bookmarks/:id/tags", get(tags::bookmark_tags))
+        .route(
+            "/bookmarks/:id/tags/:name",
+            put(tags::tag_bookmark).delete(tags::untag_bookmark),
+        )
+        .route("/export/backup", get(exports::export_backup))
+        .route("/export/csv", get(exports::export_csv))
+        .route("/export/dashy", get(exports::export_dashy))
+        .route("/export/heimdall", get(exports::export_heimdall))
+        .route("/export/homer", get(exports::export_homer))
+        .route("/export/netscape", get(exports::export_netscape))
+        .route("/export/xbel", get(exports::export_xbel))
+        .route(
+            "/import/backup",
+            post(imports::import_backup).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/csv",
+            post(imports::import_csv).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/dashy",
+            post(imports::import_dashy).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/flame",
+            post(imports::import_flame).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/heimdall",
+            post(imports::import_heimdall).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/homer",
+            post(imports::import_homer).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/netscape",
+            post(imports::import_netscape).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/import/xbel",
+            post(imports::import_xbel).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route("/add", get(quick_add::quick_add))
+        .route(
+            "/api/bookmarks/",
+            get(linkding::bookmarks).post(linkding::add_bookmark),
+        )
+        .route(
+            "/api/bookmarks/archived/",
+            get(linkding::archived_bookmarks),
+        )
+        .route("/api/bookmarks/check/", get(linkding::check_bookmark))
+        .route(
+            "/api/bookmarks/:id/",
+            get(linkding::get_bookmark)
+                .put(linkding::update_bookmark)
+                .patch(linkding::update_bookmark)
+                .delete(linkding::delete_bookmark),
+        )
+        .route(
+            "/api/bookmarks/:id/archive/",
+            post(linkding::archive_bookmark),
+        )
+        .route(
+            "/api/bookmarks/:id/unarchive/",
+            post(linkding::unarchive_bookmark),
+        )
+        .route("/api/tags/", get(linkding::tags).post(linkding::add_tag))
+        .route("/api/tags/:id/", get(linkding::get_tag))
+        .route("/duplicates", get(duplicates::duplicates))
+        .route("/icons/:id", get(icons::get_icon))
+        .route("/links", get(link_checks::links))
+        .route("/metadata", get(page_metadata::page_metadata))
+        .route("/opensearch", get(open_search::search))
+        .route("/opensearch.xml", get(open_search::description))
+        .route("/opensearch/suggestions", get(open_search::suggestions))
+        .route("/search", get(search::search))
+        .route("/search/dispatch", get(search::dispatch))
+        .route("/tags", get(tags::tags))
+        .route("/tags/:name", delete(tags::delete_tag))
+        .route("/groups", get(groups::groups).post(groups::add_group))
+        .route("/groups/:id", delete(groups::delete_group))
+        .route(
+            "/groups/:id/members/:user_id",
+            put(groups::add_member).delete(groups::remove_member),
+        )
+        .route("/tokens", get(tokens::tokens).post(tokens::add_token))
+        .route("/tokens/:id", delete(tokens::delete_token))
+        .route("/trash", get(trashed::trash).delete(trashed::empty_trash))
+        .route(
+            "/trash/applications/:id",
+            delete(trashed::purge_application),
+        )
+        .route(
+            "/trash/applications/:id/restore",
+            post(trashed::restore_application),
+        )
+        .route("/trash/bookmarks/:id", delete(trashed::purge_bookmark))
+        .route(
+            "/trash/bookmarks/:id/restore",
+            post(trashed::restore_bookmark),
+        )
+        .route("/trash/categories/:id", delete(trashed::purge_category))
+        .route(
+            "/trash/categories/:id/restore",
+            post(trashed::restore_category),
+        )
+        .route("/visits/:id", post(visits::add_visit))
         // .route("/components", post(components::fetch_components))
         .route("/pusher/auth", post(pusher::pusher_auth))
         .route("/pusher/test", get(pusher::pusher_test))
@@ -64,7 +289,13 @@ async fn main() -> anyhow::Result<()> {
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
-                .allow_methods(vec![Method::GET, Method::POST, Method::PUT])
+                .allow_methods(vec![
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                ])
                 .allow_headers(vec![header::AUTHORIZATION, header::CONTENT_TYPE]),
         )
         .layer(
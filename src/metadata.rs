@@ -0,0 +1,295 @@
+//! Page metadata extraction. Linked page is fetched and its `<title>`, meta description,
+//! canonical URL, OpenGraph and Twitter card properties are extracted, so that bookmarks
+//! and applications can be prefilled with them or refreshed later.
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::{
+    errors::RequestError,
+    http::{self, Outbound},
+    models::{
+        application::{self, Application, ApplicationDetails},
+        bookmark::{self, Bookmark, BookmarkDetails},
+        user::User,
+    },
+    notes::Notes,
+    urls,
+};
+
+const PAGE_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Metadata extracted from a page. `title` and `description` are the best of the page's
+/// own ones, OpenGraph and Twitter card ones.
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct PageMetadata {
+    /// URL page was fetched from, after following redirects.
+    pub url: String,
+    pub canonical_url: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+    /// OpenGraph properties, without the `og:` prefix.
+    pub open_graph: BTreeMap<String, String>,
+    /// Twitter card properties, without the `twitter:` prefix.
+    pub twitter: BTreeMap<String, String>,
+}
+
+/// Collapses whitespace of extracted text, skipping the empty one.
+fn clean(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Extracts metadata of HTML page fetched from `page_url`.
+pub fn extract_metadata(html: &str, page_url: &Url) -> PageMetadata {
+    let document = Html::parse_document(html);
+    let title = Selector::parse("title").expect("Invalid selector");
+    let meta = Selector::parse("meta[content]").expect("Invalid selector");
+    let canonical = Selector::parse("link[rel][href]").expect("Invalid selector");
+
+    let mut metadata = PageMetadata {
+        url: page_url.to_string(),
+        ..Default::default()
+    };
+    let mut description = None;
+
+    for element in document.select(&meta) {
+        let element = element.value();
+        let Some(content) = element.attr("content").and_then(clean) else {
+            continue;
+        };
+        // OpenGraph uses `property`, though `name` is common in the wild too
+        let Some(key) = element.attr("property").or(element.attr("name")) else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+
+        if let Some(property) = key.strip_prefix("og:") {
+            metadata
+                .open_graph
+                .entry(property.to_string())
+                .or_insert(content);
+        } else if let Some(property) = key.strip_prefix("twitter:") {
+            metadata
+                .twitter
+                .entry(property.to_string())
+                .or_insert(content);
+        } else if key == "description" {
+            description.get_or_insert(content);
+        }
+    }
+
+    metadata.canonical_url = document
+        .select(&canonical)
+        .find(|link| {
+            link.value().attr("rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|r| r.eq_ignore_ascii_case("canonical"))
+            })
+        })
+        .and_then(|link| link.value().attr("href"))
+        .or(metadata.open_graph.get("url").map(String::as_str))
+        .and_then(|href| page_url.join(href.trim()).ok())
+        .map(|url| url.to_string());
+
+    let property = |name: &str| {
+        metadata
+            .open_graph
+            .get(name)
+            .or(metadata.twitter.get(name))
+            .cloned()
+    };
+    metadata.title = property("title").or_else(|| {
+        document
+            .select(&title)
+            .next()
+            .and_then(|t| clean(&t.text().collect::<String>()))
+    });
+    metadata.description = property("description").or(description);
+    metadata.image = property("image")
+        .and_then(|src| page_url.join(&src).ok())
+        .map(|url| url.to_string());
+    metadata.site_name = metadata.open_graph.get("site_name").cloned();
+
+    metadata
+}
+
+/// Fetches page of given URL and extracts its metadata. Pages other than HTML ones
+/// have no metadata but the URL they were fetched from.
+pub async fn fetch_metadata(outbound: &Outbound, url: &str) -> anyhow::Result<PageMetadata> {
+    let url = urls::validate_url(url)?;
+    let page = outbound
+        .fetch(url.clone(), PAGE_SIZE_LIMIT)
+        .await
+        .map_err(|e| RequestError::Invalid("url", format!("{url} ({e})")))?;
+
+    if !page.status.is_success() {
+        return Err(RequestError::Invalid(
+            "url",
+            format!("{url} (responded with {})", page.status),
+        )
+        .into());
+    }
+    let html = page
+        .media_type()
+        .is_none_or(|mt| mt == "text/html" || mt == "application/xhtml+xml");
+
+    Ok(match html {
+        true => extract_metadata(&String::from_utf8_lossy(&page.body), &page.url),
+        false => PageMetadata {
+            url: page.url.to_string(),
+            ..Default::default()
+        },
+    })
+}
+
+/// Fetches metadata of given URL with the shared outbound client.
+pub async fn page_metadata(url: &str) -> anyhow::Result<PageMetadata> {
+    fetch_metadata(http::outbound(), url).await
+}
+
+/// Refreshes bookmark's name with the current title of its page.
+pub async fn refresh_bookmark_metadata(
+    pool: &SqlitePool,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Bookmark> {
+    let bookmark = bookmark::find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    let metadata = page_metadata(&bookmark.url).await?;
+
+    let details = BookmarkDetails {
+        category_id: bookmark.category_id,
+        name: metadata.title.unwrap_or(bookmark.name),
+        url: bookmark.url,
+        icon: bookmark.icon,
+        notes: bookmark.notes.map(Notes::into_markdown),
+        visible: bookmark.visible,
+    };
+    bookmark::update_bookmark(pool, user, bookmark_id, details).await
+}
+
+/// Refreshes application's name and description with the current ones of its page.
+pub async fn refresh_application_metadata(
+    pool: &SqlitePool,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Application> {
+    let application = application::find_own_application(pool, user, application_id)
+        .await?
+        .ok_or(RequestError::NotFound("Application"))?;
+    let metadata = page_metadata(&application.url).await?;
+
+    let details = ApplicationDetails {
+        category_id: application.category_id,
+        name: metadata.title.unwrap_or(application.name),
+        description: metadata.description.or(application.description),
+        url: application.url,
+        icon: application.icon,
+        notes: application.notes.map(Notes::into_markdown),
+        visible: application.visible,
+        shared: application.shared,
+        group_id: application.group_id,
+        searchable: application.searchable,
+        search_url: application.search_url,
+        keyword: application.keyword,
+    };
+    application::update_application(pool, user, application_id, details).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn prefers_open_graph_and_twitter_properties() {
+        let html = r#"<html><head>
+            <title>  Page
+                title </title>
+            <meta name="description" content="Page description">
+            <meta property="og:title" content="OpenGraph title">
+            <meta name="twitter:title" content="Twitter title">
+            <meta name="twitter:description" content="Twitter description">
+            <meta property="og:site_name" content="Example">
+        </head></html>"#;
+        let metadata = extract_metadata(html, &url("https://example.com/a"));
+
+        assert_eq!(metadata.title.as_deref(), Some("OpenGraph title"));
+        assert_eq!(metadata.description.as_deref(), Some("Twitter description"));
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+        assert_eq!(metadata.open_graph["title"], "OpenGraph title");
+        assert_eq!(metadata.twitter["title"], "Twitter title");
+    }
+
+    #[test]
+    fn falls_back_to_page_title_and_description() {
+        let html = r#"<title>Page
+            title</title><meta name="Description" content=" Page  description ">"#;
+        let metadata = extract_metadata(html, &url("https://example.com/a"));
+
+        assert_eq!(metadata.title.as_deref(), Some("Page title"));
+        assert_eq!(metadata.description.as_deref(), Some("Page description"));
+    }
+
+    #[test]
+    fn resolves_relative_urls_against_page() {
+        let html = r#"<head>
+            <link rel="alternate canonical" href="/articles/1">
+            <meta property="og:image" content="../img/cover.png">
+        </head>"#;
+        let metadata = extract_metadata(html, &url("https://example.com/blog/post?id=1"));
+
+        assert_eq!(metadata.url, "https://example.com/blog/post?id=1");
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/articles/1")
+        );
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://example.com/img/cover.png")
+        );
+    }
+
+    #[test]
+    fn takes_canonical_url_from_open_graph() {
+        let html = r#"<meta property="og:url" content="/canonical">"#;
+        let metadata = extract_metadata(html, &url("https://example.com/a?utm_source=x"));
+
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/canonical")
+        );
+    }
+
+    #[test]
+    fn skips_missing_and_empty_tags() {
+        let html = r#"<title> </title>
+            <meta property="og:title" content="  ">
+            <meta name="description">
+            <meta content="no name">"#;
+        let metadata = extract_metadata(html, &url("https://example.com/"));
+
+        assert_eq!(
+            metadata,
+            PageMetadata {
+                url: "https://example.com/".to_string(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            extract_metadata("", &url("https://example.com/")).title,
+            None
+        );
+    }
+}
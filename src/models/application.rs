@@ -1,11 +1,16 @@
 use hugsqlx::{params, HugSqlx};
 use serde::{Deserialize, Serialize};
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use time::OffsetDateTime;
 use uuid::Uuid;
 
-use crate::errors::InternalError;
+use crate::{
+    errors::{InternalError, RequestError},
+    notes::{self, Notes},
+    urls,
+};
 
-use super::user::User;
+use super::{category, group, user::User};
 
 #[derive(HugSqlx)]
 #[queries = "resources/db/queries/applications.sql"]
@@ -14,25 +19,452 @@ struct Applications {}
 #[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
 pub struct Application {
     #[sqlx(rename = "application_id")]
-    id: Uuid,
-    name: String,
-    description: Option<String>,
-    url: String,
-    icon: String,
-    visible: bool,
-    position: u16,
+    pub id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub icon: Option<String>,
+    /// Markdown notes on the application.
+    #[serde(default)]
+    pub notes: Option<Notes>,
+    #[sqlx(rename = "visibility")]
+    pub visible: bool,
+    pub shared: bool,
+    /// Group the application is shared with, or none if it's shared with everyone.
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
+    pub searchable: bool,
+    /// Template of URL searching within the application, with `{q}` standing for terms.
+    #[serde(default)]
+    pub search_url: Option<String>,
+    /// Keyword of address-bar queries dispatched to the application, like `gh`.
+    #[serde(default)]
+    pub keyword: Option<String>,
+    pub position: u16,
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub created_at: Option<OffsetDateTime>,
+    /// Set when recent link check found the URL dead.
+    #[serde(default)]
+    pub broken: bool,
+    /// Path of icon discovered on linked page.
+    #[serde(default)]
+    pub favicon: Option<String>,
+    /// Score of how frequently and recently the item's been visited.
+    #[serde(default)]
+    pub frecency: u32,
+    /// Set for applications shared by admins, which user can only hide or reorder.
+    #[serde(default)]
+    pub managed: bool,
+}
+
+/// Application properties provided by user when creating or updating an application.
+#[derive(Deserialize, Debug)]
+pub struct ApplicationDetails {
+    pub category_id: Option<Uuid>,
+    #[serde(alias = "title")]
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default = "default_visibility")]
+    pub visible: bool,
+    #[serde(default)]
+    pub shared: bool,
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
+    #[serde(default)]
+    pub searchable: bool,
+    #[serde(default)]
+    pub search_url: Option<String>,
+    #[serde(default)]
+    pub keyword: Option<String>,
+}
+
+/// User's own view of application shared with them. Properties not given are kept as
+/// they are.
+#[derive(Deserialize, Debug)]
+pub struct ApplicationOverlay {
+    pub visible: Option<bool>,
+    pub position: Option<u16>,
+}
+
+fn default_visibility() -> bool {
+    true
+}
+
+impl ApplicationDetails {
+    /// Validates and normalizes application details. Application might be optionally
+    /// grouped in one of user's categories. Only admins can share applications, either
+    /// with everyone or with a group. Searchable applications need a search URL template.
+    /// Keywords are lowercased single words.
+    async fn validate(mut self, conn: &mut SqliteConnection, user: &User) -> anyhow::Result<Self> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err(RequestError::Invalid("name", "name cannot be empty".into()).into());
+        }
+        self.description = self
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        self.url = urls::canonical_url(&self.url)?.to_string();
+        self.notes = notes::validate_notes(self.notes)?;
+        self.search_url = self
+            .search_url
+            .filter(|template| !template.trim().is_empty())
+            .map(|template| urls::validate_search_url(&template))
+            .transpose()?;
+        self.keyword = self
+            .keyword
+            .map(|keyword| keyword.trim().to_lowercase())
+            .filter(|keyword| !keyword.is_empty());
+        if let Some(keyword) = &self.keyword {
+            if keyword.contains(char::is_whitespace) {
+                return Err(RequestError::Invalid(
+                    "keyword",
+                    format!("{keyword} (keyword must be a single word)"),
+                )
+                .into());
+            }
+        }
+        if self.searchable && self.search_url.is_none() {
+            return Err(RequestError::Invalid(
+                "search_url",
+                "searchable applications need a search URL".into(),
+            )
+            .into());
+        }
+        if let Some(category_id) = &self.category_id {
+            if category::find_category_in(conn, user, category_id)
+                .await?
+                .is_none()
+            {
+                return Err(RequestError::NotFound("Category").into());
+            }
+        }
+        if self.shared {
+            user.ensure_admin("share applications")?;
+        }
+        if let Some(group_id) = &self.group_id {
+            if !self.shared {
+                return Err(RequestError::Invalid(
+                    "group_id",
+                    "only shared applications can be shared with a group".into(),
+                )
+                .into());
+            }
+            if group::find_group_in(conn, group_id).await?.is_none() {
+                return Err(RequestError::NotFound("Group").into());
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn fetch_error(e: sqlx::Error) -> InternalError {
+    tracing::error!(error = ?e, "Could load user's applications");
+    InternalError::AppsFetch
 }
 
 pub async fn fetch_applications(
     pool: &Pool<Sqlite>,
     user: &User,
+    tag: Option<&str>,
 ) -> anyhow::Result<Vec<Application>> {
+    fetch_applications_in(&mut *pool.acquire().await.map_err(fetch_error)?, user, tag).await
+}
+
+/// Fetches user's applications using given connection, like the one of a transaction.
+pub async fn fetch_applications_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    tag: Option<&str>,
+) -> anyhow::Result<Vec<Application>> {
+    let tag = tag.map(str::to_lowercase);
     Ok(
-        Applications::fetch_applications_for_user_id::<_, Application>(pool, params!(user.id))
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "Could load user's applications");
-                InternalError::AppsFetch
-            })?,
+        Applications::fetch_applications_for_user_id::<_, Application>(
+            conn,
+            params!(user.id, &tag),
+        )
+        .await
+        .map_err(fetch_error)?,
+    )
+}
+
+pub async fn find_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Option<Application>> {
+    find_application_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        application_id,
+    )
+    .await
+}
+
+/// Finds user's application using given connection, like the one of a transaction.
+pub async fn find_application_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Option<Application>> {
+    Ok(Applications::fetch_application_by_id::<_, Application>(
+        conn,
+        params!(application_id, user.id),
+    )
+    .await
+    .map_err(fetch_error)?)
+}
+
+/// Fetches applications defined by user, leaving out the ones shared with user by others.
+pub async fn fetch_own_applications(
+    pool: &Pool<Sqlite>,
+    user: &User,
+) -> anyhow::Result<Vec<Application>> {
+    fetch_own_applications_in(&mut *pool.acquire().await.map_err(fetch_error)?, user).await
+}
+
+pub async fn fetch_own_applications_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+) -> anyhow::Result<Vec<Application>> {
+    let mut applications = fetch_applications_in(conn, user, None).await?;
+    applications.retain(|application| !application.managed);
+    Ok(applications)
+}
+
+/// Finds application defined by user, as opposed to the ones shared with user by others.
+pub async fn find_own_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Option<Application>> {
+    find_own_application_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        application_id,
+    )
+    .await
+}
+
+pub async fn find_own_application_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Option<Application>> {
+    Ok(find_application_in(conn, user, application_id)
+        .await?
+        .filter(|application| !application.managed))
+}
+
+/// Makes sure none of user's other applications has the same keyword. Applications shared
+/// with user might share the keyword, as user's own application takes precedence.
+async fn ensure_unique_keyword(
+    conn: &mut SqliteConnection,
+    user: &User,
+    details: &ApplicationDetails,
+    application_id: Option<&Uuid>,
+) -> anyhow::Result<()> {
+    let Some(keyword) = &details.keyword else {
+        return Ok(());
+    };
+    if fetch_own_applications_in(conn, user)
+        .await?
+        .iter()
+        .any(|a| a.keyword.as_ref() == Some(keyword) && Some(&a.id) != application_id)
+    {
+        return Err(RequestError::Conflict("keyword", keyword.clone()).into());
+    }
+    Ok(())
+}
+
+pub async fn create_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    details: ApplicationDetails,
+) -> anyhow::Result<Application> {
+    create_application_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        details,
+    )
+    .await
+}
+
+/// Creates new application using given connection, like the one of a transaction.
+pub async fn create_application_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    details: ApplicationDetails,
+) -> anyhow::Result<Application> {
+    let details = details.validate(conn, user).await?;
+    ensure_unique_keyword(conn, user, &details, None).await?;
+    Ok(Applications::create_new_application(
+        conn,
+        params!(
+            Uuid::new_v4(),
+            user.id,
+            &details.name,
+            &details.description,
+            &details.url,
+            &details.icon,
+            details.visible,
+            details.shared,
+            details.searchable,
+            details.category_id,
+            &details.notes,
+            details.group_id,
+            &details.search_url,
+            &details.keyword
+        ),
+    )
+    .await
+    .map(|row| Application {
+        id: row.get(0),
+        position: row.get(1),
+        created_at: row.get(2),
+        broken: row.get(3),
+        favicon: None,
+        frecency: 0,
+        managed: false,
+        category_id: details.category_id,
+        name: details.name,
+        description: details.description,
+        url: details.url,
+        icon: details.icon,
+        notes: details.notes.map(Notes::from),
+        visible: details.visible,
+        shared: details.shared,
+        group_id: details.group_id,
+        searchable: details.searchable,
+        search_url: details.search_url,
+        keyword: details.keyword,
+    })
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't create new application");
+        InternalError::AppsCreate
+    })?)
+}
+
+pub async fn update_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+    details: ApplicationDetails,
+) -> anyhow::Result<Application> {
+    let mut conn = pool.acquire().await.map_err(fetch_error)?;
+    let details = details.validate(&mut conn, user).await?;
+    ensure_unique_keyword(&mut conn, user, &details, Some(application_id)).await?;
+    let result = Applications::update_application(
+        &mut *conn,
+        params!(
+            application_id,
+            user.id,
+            &details.name,
+            &details.description,
+            &details.url,
+            &details.icon,
+            details.visible,
+            details.shared,
+            details.searchable,
+            details.category_id,
+            &details.notes,
+            details.group_id,
+            &details.search_url,
+            &details.keyword
+        ),
     )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't update application");
+        InternalError::AppsUpdate
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    find_application_in(&mut conn, user, application_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Application").into())
+}
+
+/// Moves user's application to trash, from where it can be restored or purged.
+pub async fn delete_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Applications::delete_application(pool, params!(application_id, user.id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't delete application");
+            InternalError::AppsDelete
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    Ok(())
+}
+
+/// Hides or reorders application shared with user, leaving the shared application intact.
+pub async fn set_overlay(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+    overlay: ApplicationOverlay,
+) -> anyhow::Result<Application> {
+    ensure_managed(pool, user, application_id).await?;
+    Applications::upsert_application_overlay(
+        pool,
+        params!(user.id, application_id, overlay.visible, overlay.position),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't update application's overlay");
+        InternalError::AppsUpdate
+    })?;
+    find_application(pool, user, application_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Application").into())
+}
+
+/// Reverts application shared with user to how it's been shared.
+pub async fn reset_overlay(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Application> {
+    let application = ensure_managed(pool, user, application_id).await?;
+    Applications::delete_application_overlay(pool, params!(user.id, application_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't delete application's overlay");
+            InternalError::AppsUpdate
+        })?;
+    find_application(pool, user, &application.id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Application").into())
+}
+
+async fn ensure_managed(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Application> {
+    let application = find_application(pool, user, application_id)
+        .await?
+        .ok_or(RequestError::NotFound("Application"))?;
+    if !application.managed {
+        return Err(RequestError::Invalid(
+            "application",
+            "only applications shared by others can be overlaid".into(),
+        )
+        .into());
+    }
+    Ok(application)
 }
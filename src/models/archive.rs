@@ -0,0 +1,102 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use hugsqlx::{params, HugSqlx};
+use serde::Serialize;
+use sqlx::{Pool, Row, Sqlite};
+use std::io::{Read, Write};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::user::User;
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/archives.sql"]
+struct Archives {}
+
+/// Snapshot of bookmarked page, stored gzip-compressed.
+#[derive(Debug, sqlx::FromRow)]
+pub struct Archive {
+    pub content: Vec<u8>,
+    pub size: i64,
+    pub captured_at: OffsetDateTime,
+}
+
+impl Archive {
+    /// Decompressed HTML of archived page.
+    pub fn html(&self) -> anyhow::Result<String> {
+        let mut html = String::with_capacity(self.size as usize);
+        GzDecoder::new(self.content.as_slice()).read_to_string(&mut html)?;
+        Ok(html)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ArchiveInfo {
+    pub bookmark_id: Uuid,
+    pub url: String,
+    /// Size of archived page, before compression.
+    pub size: usize,
+    #[serde(with = "time::serde::rfc3339")]
+    pub captured_at: OffsetDateTime,
+}
+
+pub async fn store_archive(
+    pool: &Pool<Sqlite>,
+    bookmark_id: &Uuid,
+    url: &str,
+    html: &str,
+) -> anyhow::Result<ArchiveInfo> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(html.as_bytes())?;
+    let content = encoder.finish()?;
+
+    let captured_at: OffsetDateTime =
+        Archives::upsert_archive(pool, params!(bookmark_id, url, content, html.len() as i64))
+            .await
+            .map(|row| row.get(0))
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't store archived page");
+                InternalError::ArchivesUpdate
+            })?;
+
+    Ok(ArchiveInfo {
+        bookmark_id: *bookmark_id,
+        url: url.to_string(),
+        size: html.len(),
+        captured_at,
+    })
+}
+
+pub async fn find_archive(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Option<Archive>> {
+    Ok(
+        Archives::fetch_archive_by_bookmark_id::<_, Archive>(pool, params!(bookmark_id, user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load archived page");
+                InternalError::ArchivesFetch
+            })?,
+    )
+}
+
+pub async fn delete_archive(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Archives::delete_archive(pool, params!(bookmark_id, user.id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't delete archived page");
+            InternalError::ArchivesUpdate
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Archive").into());
+    }
+    Ok(())
+}
@@ -1,11 +1,16 @@
 use hugsqlx::{params, HugSqlx};
 use serde::{Deserialize, Serialize};
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use time::OffsetDateTime;
 use uuid::Uuid;
 
-use crate::errors::InternalError;
+use crate::{
+    errors::{InternalError, RequestError},
+    notes::{self, Notes},
+    urls,
+};
 
-use super::user::User;
+use super::{category, user::User};
 
 #[derive(HugSqlx)]
 #[queries = "resources/db/queries/bookmarks.sql"]
@@ -13,23 +18,254 @@ struct Bookmarks {}
 
 #[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
 pub struct Bookmark {
-    #[sqlx(rename = "link_id")]
-    id: Uuid,
-    category_id: Uuid,
-    name: String,
-    url: String,
-    icon: String,
-    visible: bool,
-    position: u16,
+    #[sqlx(rename = "bookmark_id")]
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub icon: Option<String>,
+    /// Markdown notes on the bookmark.
+    #[serde(default)]
+    pub notes: Option<Notes>,
+    #[sqlx(rename = "visibility")]
+    pub visible: bool,
+    pub position: u16,
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub created_at: Option<OffsetDateTime>,
+    /// Set when recent link check found the URL dead.
+    #[serde(default)]
+    pub broken: bool,
+    /// Path of icon discovered on linked page.
+    #[serde(default)]
+    pub favicon: Option<String>,
+    /// Score of how frequently and recently the item's been visited.
+    #[serde(default)]
+    pub frecency: u32,
+    /// Capture time of offline snapshot of bookmarked page, if there's any.
+    #[serde(with = "time::serde::rfc3339::option", default)]
+    pub archived_at: Option<OffsetDateTime>,
+}
+
+/// Bookmark properties provided by user when creating or updating a bookmark.
+#[derive(Deserialize, Debug)]
+pub struct BookmarkDetails {
+    pub category_id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default = "default_visibility")]
+    pub visible: bool,
+}
+
+fn default_visibility() -> bool {
+    true
+}
+
+impl BookmarkDetails {
+    /// Validates and normalizes bookmark details. Category has to be one of user's categories.
+    async fn validate(mut self, conn: &mut SqliteConnection, user: &User) -> anyhow::Result<Self> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err(RequestError::Invalid("name", "name cannot be empty".into()).into());
+        }
+        self.url = urls::canonical_url(&self.url)?.to_string();
+        self.notes = notes::validate_notes(self.notes)?;
+        if category::find_category_in(conn, user, &self.category_id)
+            .await?
+            .is_none()
+        {
+            return Err(RequestError::NotFound("Category").into());
+        }
+        Ok(self)
+    }
+}
+
+fn fetch_error(e: sqlx::Error) -> InternalError {
+    tracing::error!(error = ?e, "Could load user's bookmarks");
+    InternalError::LinksFetch
+}
+
+pub async fn fetch_bookmarks(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    tag: Option<&str>,
+) -> anyhow::Result<Vec<Bookmark>> {
+    fetch_bookmarks_in(&mut *pool.acquire().await.map_err(fetch_error)?, user, tag).await
 }
 
-pub async fn fetch_bookmarks(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<Bookmark>> {
+/// Fetches user's bookmarks using given connection, like the one of a transaction.
+pub async fn fetch_bookmarks_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    tag: Option<&str>,
+) -> anyhow::Result<Vec<Bookmark>> {
+    let tag = tag.map(str::to_lowercase);
     Ok(
-        Bookmarks::fetch_bookmarks_for_user_id::<_, Bookmark>(pool, params!(user.id))
+        Bookmarks::fetch_bookmarks_for_user_id::<_, Bookmark>(conn, params!(user.id, &tag))
             .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "Could load user's applications");
-                InternalError::LinksFetch
-            })?,
+            .map_err(fetch_error)?,
+    )
+}
+
+pub async fn find_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Option<Bookmark>> {
+    find_bookmark_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        bookmark_id,
+    )
+    .await
+}
+
+pub async fn find_bookmark_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Option<Bookmark>> {
+    Ok(
+        Bookmarks::fetch_bookmark_by_id::<_, Bookmark>(conn, params!(bookmark_id, user.id))
+            .await
+            .map_err(fetch_error)?,
     )
 }
+
+pub async fn create_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    details: BookmarkDetails,
+) -> anyhow::Result<Bookmark> {
+    create_bookmark_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        details,
+        None,
+    )
+    .await
+}
+
+/// Creates a new bookmark using given connection, like the one of an import's transaction.
+/// Bookmark might have explicitly provided creation time, like the one preserved from
+/// imported bookmarks. Current time is assumed if no `created_at` is provided.
+pub async fn create_bookmark_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    details: BookmarkDetails,
+    created_at: Option<OffsetDateTime>,
+) -> anyhow::Result<Bookmark> {
+    let details = details.validate(&mut *conn, user).await?;
+    Ok(Bookmarks::create_new_bookmark(
+        conn,
+        params!(
+            Uuid::new_v4(),
+            user.id,
+            details.category_id,
+            &details.name,
+            &details.url,
+            &details.icon,
+            details.visible,
+            created_at,
+            &details.notes
+        ),
+    )
+    .await
+    .map(|row| Bookmark {
+        id: row.get(0),
+        position: row.get(1),
+        created_at: row.get(2),
+        broken: row.get(3),
+        favicon: None,
+        frecency: 0,
+        archived_at: None,
+        category_id: details.category_id,
+        name: details.name,
+        url: details.url,
+        icon: details.icon,
+        notes: details.notes.map(Notes::from),
+        visible: details.visible,
+    })
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't create new bookmark");
+        InternalError::BookmarksCreate
+    })?)
+}
+
+pub async fn update_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    details: BookmarkDetails,
+) -> anyhow::Result<Bookmark> {
+    let mut conn = pool.acquire().await.map_err(fetch_error)?;
+    let details = details.validate(&mut conn, user).await?;
+    let result = Bookmarks::update_bookmark(
+        &mut *conn,
+        params!(
+            bookmark_id,
+            user.id,
+            &details.name,
+            &details.url,
+            &details.icon,
+            details.visible,
+            details.category_id,
+            &details.notes
+        ),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't update bookmark");
+        InternalError::BookmarksUpdate
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    find_bookmark_in(&mut conn, user, bookmark_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Bookmark").into())
+}
+
+pub async fn set_bookmark_visibility(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    visible: bool,
+) -> anyhow::Result<Bookmark> {
+    let result =
+        Bookmarks::update_bookmark_visibility(pool, params!(bookmark_id, user.id, visible))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't change bookmark visibility");
+                InternalError::BookmarksUpdate
+            })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Bookmark").into())
+}
+
+/// Moves user's bookmark to trash, from where it can be restored or purged.
+pub async fn delete_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Bookmarks::delete_bookmark(pool, params!(bookmark_id, user.id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't delete bookmark");
+            InternalError::BookmarksDelete
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use hugsqlx::{params, HugSqlx};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteQueryResult, Pool, Sqlite, SqliteConnection};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::{application, bookmark, category, tag, user::User};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/bulk.sql"]
+struct Bulk {}
+
+/// Action performed on each of bulk operation's items.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkAction {
+    /// Moves items to category. Applications might be moved out of any category.
+    Move {
+        category_id: Option<Uuid>,
+    },
+    Delete,
+    Hide,
+    Show,
+    Visibility {
+        visible: bool,
+    },
+    Tag {
+        tag: String,
+    },
+    Untag {
+        tag: String,
+    },
+}
+
+/// Outcome of bulk action performed on a single item.
+#[derive(Serialize, Debug)]
+pub struct BulkResult {
+    pub id: Uuid,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Items {
+    Bookmarks,
+    Applications,
+}
+
+impl Items {
+    fn name(self) -> &'static str {
+        match self {
+            Items::Bookmarks => "Bookmark",
+            Items::Applications => "Application",
+        }
+    }
+}
+
+/// Validated bulk action.
+enum Operation {
+    Move(Option<Uuid>),
+    Delete,
+    Visibility(bool),
+    Tag(String),
+    Untag(String),
+}
+
+fn bulk_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't perform bulk operation");
+    InternalError::BulkUpdate
+}
+
+/// Validates action before any item gets touched. Items may be moved only to one of
+/// user's categories.
+async fn validate(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    items: Items,
+    action: BulkAction,
+) -> anyhow::Result<BulkAction> {
+    match action {
+        BulkAction::Move { category_id: None } if matches!(items, Items::Bookmarks) => {
+            Err(RequestError::Invalid(
+                "category_id",
+                "bookmarks have to belong to a category".into(),
+            )
+            .into())
+        }
+        BulkAction::Move {
+            category_id: Some(category_id),
+        } => match category::find_category(pool, user, &category_id).await? {
+            Some(_) => Ok(BulkAction::Move {
+                category_id: Some(category_id),
+            }),
+            None => Err(RequestError::NotFound("Category").into()),
+        },
+        BulkAction::Tag { tag } => Ok(BulkAction::Tag {
+            tag: tag::normalize_tag(&tag)?,
+        }),
+        BulkAction::Untag { tag } => Ok(BulkAction::Untag {
+            tag: tag::normalize_tag(&tag)?,
+        }),
+        action => Ok(action),
+    }
+}
+
+fn prepare(action: BulkAction) -> Operation {
+    match action {
+        BulkAction::Move { category_id } => Operation::Move(category_id),
+        BulkAction::Delete => Operation::Delete,
+        BulkAction::Hide => Operation::Visibility(false),
+        BulkAction::Show => Operation::Visibility(true),
+        BulkAction::Visibility { visible } => Operation::Visibility(visible),
+        BulkAction::Tag { tag } => Operation::Tag(tag),
+        BulkAction::Untag { tag } => Operation::Untag(tag),
+    }
+}
+
+/// Performs operation on user's item. Items of other users (as well as applications
+/// shared with user) are reported as not found, the same way they are by single item routes.
+async fn apply(
+    conn: &mut SqliteConnection,
+    user: &User,
+    items: Items,
+    id: &Uuid,
+    operation: &Operation,
+) -> anyhow::Result<()> {
+    let owned = match items {
+        Items::Bookmarks => bookmark::find_bookmark_in(conn, user, id).await?.is_some(),
+        Items::Applications => application::find_own_application_in(conn, user, id)
+            .await?
+            .is_some(),
+    };
+    if !owned {
+        return Err(RequestError::NotFound(items.name()).into());
+    }
+
+    let result: Result<SqliteQueryResult, sqlx::Error> = match (items, operation) {
+        (Items::Bookmarks, Operation::Move(category_id)) => {
+            Bulk::move_bookmark(&mut *conn, params!(id, user.id, category_id)).await
+        }
+        (Items::Applications, Operation::Move(category_id)) => {
+            Bulk::move_application(&mut *conn, params!(id, user.id, category_id)).await
+        }
+        (Items::Bookmarks, Operation::Delete) => {
+            Bulk::delete_bookmark(&mut *conn, params!(id, user.id)).await
+        }
+        (Items::Applications, Operation::Delete) => {
+            Bulk::delete_application(&mut *conn, params!(id, user.id)).await
+        }
+        (Items::Bookmarks, Operation::Visibility(visible)) => {
+            Bulk::update_bookmark_visibility(&mut *conn, params!(id, user.id, visible)).await
+        }
+        (Items::Applications, Operation::Visibility(visible)) => {
+            Bulk::update_application_visibility(&mut *conn, params!(id, user.id, visible)).await
+        }
+        (Items::Bookmarks, Operation::Tag(tag)) => {
+            return tag::tag_bookmark_in(conn, user, id, tag).await
+        }
+        (Items::Applications, Operation::Tag(tag)) => {
+            return tag::tag_application_in(conn, user, id, tag).await
+        }
+        (Items::Bookmarks, Operation::Untag(tag)) => {
+            return tag::untag_bookmark_in(conn, user, id, tag).await
+        }
+        (Items::Applications, Operation::Untag(tag)) => {
+            return tag::untag_application_in(conn, user, id, tag).await
+        }
+    };
+    result.map_err(bulk_error)?;
+    Ok(())
+}
+
+/// Performs action on all given items within a single transaction. Items which can't be
+/// found are reported in results, not failing the whole operation.
+async fn perform(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    items: Items,
+    ids: &[Uuid],
+    action: BulkAction,
+) -> anyhow::Result<Vec<BulkResult>> {
+    let action = validate(pool, user, items, action).await?;
+
+    let operation = prepare(action);
+    let mut tx = pool.begin().await.map_err(bulk_error)?;
+    let mut results = Vec::with_capacity(ids.len());
+
+    for id in ids {
+        let error = match apply(&mut tx, user, items, id, &operation).await {
+            Ok(()) => None,
+            Err(e) => match e.downcast::<RequestError>() {
+                Ok(e) => Some(e.to_string()),
+                Err(e) => return Err(e),
+            },
+        };
+        results.push(BulkResult {
+            id: *id,
+            ok: error.is_none(),
+            error,
+        });
+    }
+    tx.commit().await.map_err(bulk_error)?;
+
+    Ok(results)
+}
+
+pub async fn bulk_bookmarks(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    ids: &[Uuid],
+    action: BulkAction,
+) -> anyhow::Result<Vec<BulkResult>> {
+    perform(pool, user, Items::Bookmarks, ids, action).await
+}
+
+pub async fn bulk_applications(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    ids: &[Uuid],
+    action: BulkAction,
+) -> anyhow::Result<Vec<BulkResult>> {
+    perform(pool, user, Items::Applications, ids, action).await
+}
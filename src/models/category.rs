@@ -1,9 +1,9 @@
 use hugsqlx::{params, HugSqlx};
 use serde::{Deserialize, Serialize};
-use sqlx::{Pool, Row, Sqlite};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
 use uuid::Uuid;
 
-use crate::errors::InternalError;
+use crate::errors::{InternalError, RequestError};
 
 use super::user::User;
 
@@ -14,9 +14,13 @@ struct Categories {}
 #[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
 pub struct Category {
     #[sqlx(rename = "category_id")]
-    id: Uuid,
-    name: String,
-    position: u8
+    pub id: Uuid,
+    pub name: String,
+    pub position: u16,
+    /// Category imported from nested folders, named by their path joined with
+    /// [`crate::formats::FOLDER_SEPARATOR`]. Such categories get nested again when exported.
+    #[serde(default)]
+    pub nested: bool,
 }
 
 pub async fn fetch_categories(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<Category>> {
@@ -26,26 +30,130 @@ pub async fn fetch_categories(pool: &Pool<Sqlite>, user: &User) -> anyhow::Resul
             .map_err(|e| {
                 tracing::error!(error = ?e, "Couldn't load user's categories");
                 InternalError::CategoriesFetch
-            })?
+            })?,
     )
 }
 
-pub async fn create_category(pool: &Pool<Sqlite>, user: &User, category_name: String) -> anyhow::Result<Category> {
+fn fetch_error(e: sqlx::Error) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't load user's category");
+    InternalError::CategoriesFetch
+}
+
+pub async fn find_category(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_id: &Uuid,
+) -> anyhow::Result<Option<Category>> {
+    find_category_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        category_id,
+    )
+    .await
+}
+
+/// Finds user's category using given connection, like the one of a transaction.
+pub async fn find_category_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    category_id: &Uuid,
+) -> anyhow::Result<Option<Category>> {
+    Ok(
+        Categories::fetch_category_by_id::<_, Category>(conn, params!(category_id, user.id))
+            .await
+            .map_err(fetch_error)?,
+    )
+}
+
+pub async fn find_category_by_name(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_name: &str,
+) -> anyhow::Result<Option<Category>> {
+    find_category_by_name_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        category_name,
+    )
+    .await
+}
+
+pub async fn find_category_by_name_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    category_name: &str,
+) -> anyhow::Result<Option<Category>> {
     Ok(
-        Categories::create_new_category(pool, params!(Uuid::new_v4(), user.id, &category_name))
+        Categories::fetch_category_by_name::<_, Category>(conn, params!(category_name, user.id))
             .await
-            .map(|row| {
-                let category_id = row.get(0);
-                let position = row.get(1);
-
-                Category {
-                    id: category_id,
-                    name: category_name,
-                    position
-                }
-            }).map_err(|e| {
-                tracing::error!(error = ?e, "Couldn't create new category");
-                InternalError::CategoriesCreate
-            })?
+            .map_err(fetch_error)?,
+    )
+}
+
+pub async fn create_category(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_name: String,
+) -> anyhow::Result<Category> {
+    create_category_in(
+        &mut *pool.acquire().await.map_err(fetch_error)?,
+        user,
+        category_name,
+        false,
+    )
+    .await
+}
+
+pub async fn create_category_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    category_name: String,
+    nested: bool,
+) -> anyhow::Result<Category> {
+    Ok(Categories::create_new_category(
+        conn,
+        params!(Uuid::new_v4(), user.id, &category_name, nested),
     )
+    .await
+    .map(|row| {
+        let category_id = row.get(0);
+        let position = row.get(1);
+
+        Category {
+            id: category_id,
+            name: category_name,
+            position,
+            nested,
+        }
+    })
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't create new category");
+        InternalError::CategoriesCreate
+    })?)
+}
+
+/// Moves user's category to trash, along with all its bookmarks.
+pub async fn delete_category(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_id: &Uuid,
+) -> anyhow::Result<()> {
+    let delete_error = |e: sqlx::Error| {
+        tracing::error!(error = ?e, "Couldn't delete category");
+        InternalError::CategoriesDelete
+    };
+    let mut tx = pool.begin().await.map_err(delete_error)?;
+
+    let result = Categories::delete_category(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(delete_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Category").into());
+    }
+    Categories::delete_category_bookmarks(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(delete_error)?;
+
+    tx.commit().await.map_err(delete_error)?;
+    Ok(())
 }
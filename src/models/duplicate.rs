@@ -0,0 +1,255 @@
+use hugsqlx::{params, HugSqlx};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use std::{collections::BTreeMap, fmt::Debug};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    errors::{InternalError, RequestError},
+    urls,
+};
+
+use super::{
+    application::{self, Application},
+    bookmark::{self, Bookmark},
+    user::User,
+};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/duplicates.sql"]
+struct Duplicates {}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateKind {
+    Bookmark,
+    Application,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DuplicateItem {
+    pub id: Uuid,
+    pub category_id: Option<Uuid>,
+    pub name: String,
+    pub url: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// Bookmarks or applications linking the same page.
+#[derive(Serialize, Debug)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    /// URL which all the items' ones boil down to.
+    pub url: String,
+    pub items: Vec<DuplicateItem>,
+}
+
+impl From<Bookmark> for DuplicateItem {
+    fn from(bookmark: Bookmark) -> Self {
+        DuplicateItem {
+            id: bookmark.id,
+            category_id: Some(bookmark.category_id),
+            name: bookmark.name,
+            url: bookmark.url,
+            created_at: bookmark.created_at,
+        }
+    }
+}
+
+impl From<Application> for DuplicateItem {
+    fn from(application: Application) -> Self {
+        DuplicateItem {
+            id: application.id,
+            category_id: application.category_id,
+            name: application.name,
+            url: application.url,
+            created_at: application.created_at,
+        }
+    }
+}
+
+fn group_duplicates<T: Into<DuplicateItem>>(
+    kind: DuplicateKind,
+    items: Vec<T>,
+) -> Vec<DuplicateGroup> {
+    let mut groups: BTreeMap<String, Vec<DuplicateItem>> = BTreeMap::new();
+    for item in items {
+        let item = item.into();
+        groups
+            .entry(urls::duplicate_key(&item.url))
+            .or_default()
+            .push(item);
+    }
+    groups
+        .into_iter()
+        .filter(|(_, items)| items.len() > 1)
+        .map(|(url, items)| DuplicateGroup { kind, url, items })
+        .collect()
+}
+
+/// Groups of user's bookmarks and applications linking the same pages.
+pub async fn fetch_duplicates(
+    pool: &Pool<Sqlite>,
+    user: &User,
+) -> anyhow::Result<Vec<DuplicateGroup>> {
+    let mut groups = group_duplicates(
+        DuplicateKind::Bookmark,
+        bookmark::fetch_bookmarks(pool, user, None).await?,
+    );
+    groups.extend(group_duplicates(
+        DuplicateKind::Application,
+        application::fetch_own_applications(pool, user).await?,
+    ));
+    Ok(groups)
+}
+
+/// Finds user's bookmark linking the same page as given URL.
+pub async fn find_bookmark_duplicate(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    url: &str,
+) -> anyhow::Result<Option<Bookmark>> {
+    let key = urls::duplicate_key(url);
+    Ok(bookmark::fetch_bookmarks(pool, user, None)
+        .await?
+        .into_iter()
+        .find(|bookmark| urls::duplicate_key(&bookmark.url) == key))
+}
+
+/// Finds user's application linking the same page as given URL.
+pub async fn find_application_duplicate(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    url: &str,
+) -> anyhow::Result<Option<Application>> {
+    let key = urls::duplicate_key(url);
+    Ok(application::fetch_own_applications(pool, user)
+        .await?
+        .into_iter()
+        .find(|application| urls::duplicate_key(&application.url) == key))
+}
+
+fn merge_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't merge duplicates");
+    InternalError::DuplicatesMerge
+}
+
+fn check_merged(id: &Uuid, merged: &[Uuid]) -> Result<(), RequestError> {
+    match merged.contains(id) {
+        true => Err(RequestError::Invalid(
+            "ids",
+            format!("{id} cannot be merged into itself"),
+        )),
+        false => Ok(()),
+    }
+}
+
+async fn merge_bookmark(
+    conn: &mut SqliteConnection,
+    user: &User,
+    bookmark_id: &Uuid,
+    merged_id: &Uuid,
+) -> Result<(), InternalError> {
+    Duplicates::merge_bookmark_tags(&mut *conn, params!(bookmark_id, merged_id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::move_visits(&mut *conn, params!(bookmark_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::move_archive(&mut *conn, params!(bookmark_id, merged_id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::merge_bookmark_favicon(&mut *conn, params!(bookmark_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::merge_bookmark_notes(&mut *conn, params!(bookmark_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::delete_bookmark(&mut *conn, params!(merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Ok(())
+}
+
+/// Merges given bookmarks into user's bookmark. Tags, visits, snapshot and favicon of
+/// merged bookmarks are taken over (as long as bookmark has none of its own), their notes
+/// are appended and merged bookmarks get deleted.
+pub async fn merge_bookmarks(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    merged: &[Uuid],
+) -> anyhow::Result<Bookmark> {
+    check_merged(bookmark_id, merged)?;
+    for id in std::iter::once(bookmark_id).chain(merged) {
+        if bookmark::find_bookmark(pool, user, id).await?.is_none() {
+            return Err(RequestError::NotFound("Bookmark").into());
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(merge_error)?;
+    for merged_id in merged {
+        merge_bookmark(&mut tx, user, bookmark_id, merged_id).await?;
+    }
+    tx.commit().await.map_err(merge_error)?;
+
+    bookmark::find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Bookmark").into())
+}
+
+async fn merge_application(
+    conn: &mut SqliteConnection,
+    user: &User,
+    application_id: &Uuid,
+    merged_id: &Uuid,
+) -> Result<(), InternalError> {
+    Duplicates::merge_application_tags(&mut *conn, params!(application_id, merged_id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::move_visits(&mut *conn, params!(application_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::merge_application_favicon(&mut *conn, params!(application_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::merge_application_notes(&mut *conn, params!(application_id, merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Duplicates::delete_application(&mut *conn, params!(merged_id, user.id))
+        .await
+        .map_err(merge_error)?;
+    Ok(())
+}
+
+/// Merges given applications into user's application. Tags, visits and favicon of merged
+/// applications are taken over (as long as application has no favicon of its own), their
+/// notes are appended and merged applications get deleted.
+pub async fn merge_applications(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+    merged: &[Uuid],
+) -> anyhow::Result<Application> {
+    check_merged(application_id, merged)?;
+    for id in std::iter::once(application_id).chain(merged) {
+        if application::find_own_application(pool, user, id)
+            .await?
+            .is_none()
+        {
+            return Err(RequestError::NotFound("Application").into());
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(merge_error)?;
+    for merged_id in merged {
+        merge_application(&mut tx, user, application_id, merged_id).await?;
+    }
+    tx.commit().await.map_err(merge_error)?;
+
+    application::find_application(pool, user, application_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Application").into())
+}
@@ -0,0 +1,172 @@
+use hugsqlx::{params, HugSqlx};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::user::{self, User};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/groups.sql"]
+struct Groups {}
+
+/// Group of users, which applications can be shared with.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct Group {
+    #[sqlx(rename = "group_id")]
+    pub id: Uuid,
+    pub name: String,
+    #[sqlx(skip)]
+    pub members: Vec<GroupMember>,
+}
+
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct GroupMember {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GroupDetails {
+    pub name: String,
+}
+
+fn group_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't update group");
+    InternalError::GroupsUpdate
+}
+
+fn fetch_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't load groups");
+    InternalError::GroupsFetch
+}
+
+async fn with_members(conn: &mut SqliteConnection, mut group: Group) -> anyhow::Result<Group> {
+    group.members = Groups::fetch_group_members::<_, GroupMember>(conn, params!(group.id))
+        .await
+        .map_err(fetch_error)?;
+    Ok(group)
+}
+
+/// Fetches all groups for admins, or the groups user is a member of for anyone else.
+pub async fn fetch_groups(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<Group>> {
+    let groups = match user.admin {
+        true => Groups::fetch_groups::<_, Group>(pool, params!()).await,
+        false => Groups::fetch_groups_for_user_id::<_, Group>(pool, params!(user.id)).await,
+    }
+    .map_err(fetch_error)?;
+
+    let mut conn = pool.acquire().await.map_err(fetch_error)?;
+    let mut result = Vec::with_capacity(groups.len());
+    for group in groups {
+        result.push(with_members(&mut conn, group).await?);
+    }
+    Ok(result)
+}
+
+pub async fn find_group(pool: &Pool<Sqlite>, group_id: &Uuid) -> anyhow::Result<Option<Group>> {
+    find_group_in(&mut *pool.acquire().await.map_err(fetch_error)?, group_id).await
+}
+
+/// Finds group using given connection, like the one of a transaction.
+pub async fn find_group_in(
+    conn: &mut SqliteConnection,
+    group_id: &Uuid,
+) -> anyhow::Result<Option<Group>> {
+    match Groups::fetch_group_by_id::<_, Group>(&mut *conn, params!(group_id))
+        .await
+        .map_err(fetch_error)?
+    {
+        Some(group) => Ok(Some(with_members(conn, group).await?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn create_group(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    details: GroupDetails,
+) -> anyhow::Result<Group> {
+    user.ensure_admin("manage groups")?;
+    let name = details.name.trim().to_string();
+    if name.is_empty() {
+        return Err(RequestError::Invalid("name", "name cannot be empty".into()).into());
+    }
+    if Groups::fetch_group_by_name::<_, Group>(pool, params!(&name))
+        .await
+        .map_err(fetch_error)?
+        .is_some()
+    {
+        return Err(RequestError::Conflict("group", name).into());
+    }
+
+    let id = Uuid::new_v4();
+    Groups::create_group(pool, params!(id, &name))
+        .await
+        .map_err(group_error)?;
+    Ok(Group {
+        id,
+        name,
+        members: Vec::new(),
+    })
+}
+
+/// Deletes group. Applications shared with the group are no longer shared with anyone.
+pub async fn delete_group(pool: &Pool<Sqlite>, user: &User, group_id: &Uuid) -> anyhow::Result<()> {
+    user.ensure_admin("manage groups")?;
+    let mut tx = pool.begin().await.map_err(group_error)?;
+
+    Groups::unshare_group_applications(&mut *tx, params!(group_id))
+        .await
+        .map_err(group_error)?;
+    let result = Groups::delete_group(&mut *tx, params!(group_id))
+        .await
+        .map_err(group_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Group").into());
+    }
+    tx.commit().await.map_err(group_error)?;
+    Ok(())
+}
+
+pub async fn add_member(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    group_id: &Uuid,
+    user_id: &Uuid,
+) -> anyhow::Result<Group> {
+    user.ensure_admin("manage groups")?;
+    if user::find_by_user_id(pool, user_id).await?.is_none() {
+        return Err(RequestError::NotFound("User").into());
+    }
+    if find_group(pool, group_id).await?.is_none() {
+        return Err(RequestError::NotFound("Group").into());
+    }
+    Groups::add_group_member(pool, params!(group_id, user_id))
+        .await
+        .map_err(group_error)?;
+    find_group(pool, group_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Group").into())
+}
+
+pub async fn remove_member(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    group_id: &Uuid,
+    user_id: &Uuid,
+) -> anyhow::Result<Group> {
+    user.ensure_admin("manage groups")?;
+    let result = Groups::remove_group_member(pool, params!(group_id, user_id))
+        .await
+        .map_err(group_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Group member").into());
+    }
+    find_group(pool, group_id)
+        .await?
+        .ok_or_else(|| RequestError::NotFound("Group").into())
+}
@@ -0,0 +1,74 @@
+use hugsqlx::{params, HugSqlx};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+use subtle_encoding::hex;
+use uuid::Uuid;
+
+use crate::errors::InternalError;
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/icons.sql"]
+struct Icons {}
+
+/// Icon image, identified by hash of its content.
+#[derive(Debug, sqlx::FromRow)]
+pub struct Icon {
+    #[sqlx(rename = "icon_id")]
+    pub id: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// Stores icon image and returns its identifier - a SHA-256 hash of its content.
+/// Identical images are stored only once.
+pub async fn store_icon(
+    pool: &Pool<Sqlite>,
+    content_type: &str,
+    content: &[u8],
+) -> anyhow::Result<String> {
+    let id = String::from_utf8(hex::encode(Sha256::digest(content)))?;
+    Icons::insert_icon(pool, params!(&id, content_type, content))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't store icon");
+            InternalError::IconsUpdate
+        })?;
+    Ok(id)
+}
+
+pub async fn find_icon(pool: &Pool<Sqlite>, icon_id: &str) -> anyhow::Result<Option<Icon>> {
+    Ok(Icons::fetch_icon_by_id::<_, Icon>(pool, params!(icon_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't load icon");
+            InternalError::IconsFetch
+        })?)
+}
+
+pub async fn set_bookmark_favicon(
+    pool: &Pool<Sqlite>,
+    bookmark_id: &Uuid,
+    icon_id: &str,
+) -> anyhow::Result<()> {
+    Icons::update_bookmark_favicon(pool, params!(bookmark_id, icon_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't assign bookmark's favicon");
+            InternalError::BookmarksUpdate
+        })?;
+    Ok(())
+}
+
+pub async fn set_application_favicon(
+    pool: &Pool<Sqlite>,
+    application_id: &Uuid,
+    icon_id: &str,
+) -> anyhow::Result<()> {
+    Icons::update_application_favicon(pool, params!(application_id, icon_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't assign application's favicon");
+            InternalError::AppsUpdate
+        })?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use hugsqlx::{params, HugSqlx};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    errors::{InternalError, RequestError},
+    notes::Notes,
+};
+
+use super::{
+    application::{self, Application, ApplicationDetails},
+    bookmark::{self, Bookmark, BookmarkDetails},
+    duplicate,
+    user::User,
+};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/links.sql"]
+struct Links {}
+
+#[derive(sqlx::FromRow)]
+struct StaleUrl {
+    url: String,
+}
+
+/// Outcome of checking a single URL.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct LinkStatus {
+    /// HTTP status of final response, if any response was received.
+    pub status: Option<u16>,
+    /// URL the link eventually redirects to.
+    pub final_url: Option<String>,
+    /// Set when every redirect on the way to `final_url` was a permanent one.
+    pub permanent_redirect: bool,
+    pub broken: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct LinkCheck {
+    pub url: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub status: LinkStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub checked_at: OffsetDateTime,
+}
+
+/// Link check of user's bookmark or application.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct LinkReport {
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub check: LinkCheck,
+}
+
+/// Returns URLs of bookmarks and applications (of all users) which haven't been checked
+/// for at least `max_age` seconds, the never checked ones go first.
+pub async fn fetch_stale_urls(
+    pool: &Pool<Sqlite>,
+    max_age: u64,
+    limit: u32,
+) -> anyhow::Result<Vec<String>> {
+    Ok(
+        Links::fetch_stale_urls::<_, StaleUrl>(pool, params!(max_age as i64, limit))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load links to check");
+                InternalError::LinkChecksFetch
+            })?
+            .into_iter()
+            .map(|u| u.url)
+            .collect(),
+    )
+}
+
+pub async fn store_link_check(
+    pool: &Pool<Sqlite>,
+    url: &str,
+    status: &LinkStatus,
+) -> anyhow::Result<()> {
+    Links::upsert_link_check(
+        pool,
+        params!(
+            url,
+            status.status,
+            &status.final_url,
+            status.permanent_redirect,
+            status.broken,
+            &status.error
+        ),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't store link check");
+        InternalError::LinkChecksUpdate
+    })?;
+    Ok(())
+}
+
+pub async fn find_link_check(pool: &Pool<Sqlite>, url: &str) -> anyhow::Result<Option<LinkCheck>> {
+    Ok(
+        Links::fetch_link_check_by_url::<_, LinkCheck>(pool, params!(url))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load link check");
+                InternalError::LinkChecksFetch
+            })?,
+    )
+}
+
+pub async fn fetch_link_reports(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    only_broken: bool,
+) -> anyhow::Result<Vec<LinkReport>> {
+    Ok(
+        Links::fetch_link_checks_for_user_id::<_, LinkReport>(pool, params!(user.id, only_broken))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's link checks");
+                InternalError::LinkChecksFetch
+            })?,
+    )
+}
+
+/// Returns URL given one permanently redirects to, according to its recent check.
+async fn permanent_redirect(pool: &Pool<Sqlite>, url: &str) -> anyhow::Result<String> {
+    find_link_check(pool, url)
+        .await?
+        .filter(|check| check.status.permanent_redirect)
+        .and_then(|check| check.status.final_url)
+        .ok_or_else(|| {
+            RequestError::Invalid("url", format!("{url} (no permanent redirect known)")).into()
+        })
+}
+
+/// Rewrites bookmark's URL to the one it permanently redirects to. New URL is validated
+/// like any other update and must not duplicate URL of user's other bookmark.
+pub async fn rewrite_bookmark_url(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Bookmark> {
+    let bookmark = bookmark::find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    let url = permanent_redirect(pool, &bookmark.url).await?;
+
+    if let Some(existing) = duplicate::find_bookmark_duplicate(pool, user, &url).await? {
+        if existing.id != bookmark.id {
+            return Err(RequestError::Conflict("bookmark", existing.url).into());
+        }
+    }
+    let details = BookmarkDetails {
+        category_id: bookmark.category_id,
+        name: bookmark.name,
+        url,
+        icon: bookmark.icon,
+        notes: bookmark.notes.map(Notes::into_markdown),
+        visible: bookmark.visible,
+    };
+    bookmark::update_bookmark(pool, user, bookmark_id, details).await
+}
+
+/// Rewrites application's URL to the one it permanently redirects to. New URL is validated
+/// like any other update and must not duplicate URL of user's other application.
+pub async fn rewrite_application_url(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Application> {
+    let application = application::find_own_application(pool, user, application_id)
+        .await?
+        .ok_or(RequestError::NotFound("Application"))?;
+    let url = permanent_redirect(pool, &application.url).await?;
+
+    if let Some(existing) = duplicate::find_application_duplicate(pool, user, &url).await? {
+        if existing.id != application.id {
+            return Err(RequestError::Conflict("application", existing.url).into());
+        }
+    }
+    let details = ApplicationDetails {
+        category_id: application.category_id,
+        name: application.name,
+        description: application.description,
+        url,
+        icon: application.icon,
+        notes: application.notes.map(Notes::into_markdown),
+        visible: application.visible,
+        shared: application.shared,
+        group_id: application.group_id,
+        searchable: application.searchable,
+        search_url: application.search_url,
+        keyword: application.keyword,
+    };
+    application::update_application(pool, user, application_id, details).await
+}
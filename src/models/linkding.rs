@@ -0,0 +1,391 @@
+use hugsqlx::{params, HugSqlx};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::fmt::Debug;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    errors::{InternalError, RequestError},
+    inbox,
+    notes::Notes,
+};
+
+use super::{
+    bookmark::{self, BookmarkDetails},
+    duplicate,
+    tag::{self, normalize_tag},
+    user::User,
+};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/linkding.sql"]
+struct Linkding {}
+
+#[derive(Debug, sqlx::FromRow)]
+struct BookmarkRow {
+    linkding_id: i64,
+    bookmark_id: Uuid,
+    name: String,
+    url: String,
+    notes: Option<Notes>,
+    visibility: bool,
+    created_at: Option<OffsetDateTime>,
+    favicon: Option<String>,
+    tag_names: Option<String>,
+}
+
+/// Bookmark as represented by linkding. Linkding identifies bookmarks with integers, so
+/// their stored linkding identifiers are used instead of UUIDs. Hidden bookmarks are the
+/// archived ones.
+#[derive(Serialize, Debug)]
+pub struct LinkdingBookmark {
+    pub id: i64,
+    #[serde(skip)]
+    pub bookmark_id: Uuid,
+    pub url: String,
+    pub title: String,
+    pub description: String,
+    pub notes: String,
+    pub web_archive_snapshot_url: Option<String>,
+    pub favicon_url: Option<String>,
+    pub preview_image_url: Option<String>,
+    pub is_archived: bool,
+    pub unread: bool,
+    pub shared: bool,
+    pub tag_names: Vec<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub date_added: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub date_modified: Option<OffsetDateTime>,
+    pub website_title: Option<String>,
+    pub website_description: Option<String>,
+}
+
+impl From<BookmarkRow> for LinkdingBookmark {
+    fn from(row: BookmarkRow) -> Self {
+        let mut tag_names: Vec<String> = row
+            .tag_names
+            .map(|names| names.split(' ').map(String::from).collect())
+            .unwrap_or_default();
+        tag_names.sort();
+
+        LinkdingBookmark {
+            id: row.linkding_id,
+            bookmark_id: row.bookmark_id,
+            url: row.url,
+            title: row.name,
+            description: String::new(),
+            notes: row.notes.map(Notes::into_markdown).unwrap_or_default(),
+            web_archive_snapshot_url: None,
+            favicon_url: row.favicon,
+            preview_image_url: None,
+            is_archived: !row.visibility,
+            unread: false,
+            shared: false,
+            tag_names,
+            date_added: row.created_at,
+            date_modified: row.created_at,
+            website_title: None,
+            website_description: None,
+        }
+    }
+}
+
+impl LinkdingBookmark {
+    /// Tells whether bookmark matches linkding's search query. Query consists of words
+    /// to be found in bookmark's title, URL or notes, and of `#tags` it has to be tagged with.
+    pub fn matches(&self, query: &str) -> bool {
+        let text = format!("{} {} {}", self.title, self.url, self.notes).to_lowercase();
+        query
+            .split_whitespace()
+            .all(|term| match term.strip_prefix('#') {
+                Some(tag) => self
+                    .tag_names
+                    .iter()
+                    .any(|name| name == &tag.to_lowercase()),
+                None => text.contains(&term.to_lowercase()),
+            })
+    }
+}
+
+/// Bookmark properties sent by linkding clients. Properties which aren't sent are left
+/// intact, and both description and notes are kept as bookmark's notes.
+#[derive(Deserialize, Debug, Default)]
+pub struct LinkdingBookmarkDetails {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub is_archived: Option<bool>,
+    pub tag_names: Option<Vec<String>>,
+}
+
+impl LinkdingBookmarkDetails {
+    fn title(&self) -> Option<&str> {
+        self.title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Joins description and notes, unless neither of them has been sent.
+    fn notes(&self) -> Option<Option<String>> {
+        if self.description.is_none() && self.notes.is_none() {
+            return None;
+        }
+        let notes = [&self.description, &self.notes]
+            .into_iter()
+            .flatten()
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Some((!notes.is_empty()).then_some(notes))
+    }
+
+    /// Normalizes sent tags, so that invalid ones are refused before anything gets written.
+    fn normalize_tags(mut self) -> Result<Self, RequestError> {
+        if let Some(names) = self.tag_names.take() {
+            let names = names
+                .iter()
+                .map(|name| normalize_tag(name))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.tag_names = Some(names);
+        }
+        Ok(self)
+    }
+}
+
+/// Tag as represented by linkding, identified by its stored linkding identifier.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct LinkdingTag {
+    #[sqlx(rename = "linkding_id")]
+    pub id: i64,
+    pub name: String,
+}
+
+fn linkding_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't load user's bookmarks");
+    InternalError::LinksFetch
+}
+
+/// Fetches user's bookmarks matching linkding's search query.
+pub async fn fetch_bookmarks(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    archived: bool,
+    query: &str,
+) -> anyhow::Result<Vec<LinkdingBookmark>> {
+    Ok(
+        Linkding::fetch_bookmarks_for_user_id::<_, BookmarkRow>(pool, params!(user.id, !archived))
+            .await
+            .map_err(linkding_error)?
+            .into_iter()
+            .map(LinkdingBookmark::from)
+            .filter(|bookmark| bookmark.matches(query))
+            .collect(),
+    )
+}
+
+pub async fn find_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    id: i64,
+) -> anyhow::Result<Option<LinkdingBookmark>> {
+    Ok(
+        Linkding::fetch_bookmark_by_linkding_id::<_, BookmarkRow>(pool, params!(id, user.id))
+            .await
+            .map_err(linkding_error)?
+            .map(LinkdingBookmark::from),
+    )
+}
+
+async fn find_bookmark_by_id(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<LinkdingBookmark> {
+    Linkding::fetch_bookmark_by_id::<_, BookmarkRow>(pool, params!(bookmark_id, user.id))
+        .await
+        .map_err(linkding_error)?
+        .map(LinkdingBookmark::from)
+        .ok_or_else(|| RequestError::NotFound("Bookmark").into())
+}
+
+/// Finds user's bookmark linking the same page as given URL.
+pub async fn find_bookmark_by_url(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    url: &str,
+) -> anyhow::Result<Option<LinkdingBookmark>> {
+    match duplicate::find_bookmark_duplicate(pool, user, url).await? {
+        Some(existing) => Ok(Some(find_bookmark_by_id(pool, user, &existing.id).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Replaces bookmark's tags with given, already normalized ones.
+async fn set_tags(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    names: &[String],
+) -> anyhow::Result<()> {
+    for current in tag::fetch_bookmark_tags(pool, user, bookmark_id).await? {
+        if !names.contains(&current.name) {
+            tag::untag_bookmark(pool, user, bookmark_id, &current.name).await?;
+        }
+    }
+    for name in names {
+        tag::tag_bookmark(pool, user, bookmark_id, name).await?;
+    }
+    Ok(())
+}
+
+/// Updates bookmark with sent properties, tags of which are expected to be normalized.
+async fn update(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    details: LinkdingBookmarkDetails,
+) -> anyhow::Result<LinkdingBookmark> {
+    let bookmark = bookmark::find_bookmark(pool, user, bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+
+    let notes = details.notes();
+    let updated = BookmarkDetails {
+        category_id: bookmark.category_id,
+        name: details.title().map(String::from).unwrap_or(bookmark.name),
+        url: details.url.clone().unwrap_or(bookmark.url),
+        icon: bookmark.icon,
+        notes: notes.unwrap_or_else(|| bookmark.notes.map(Notes::into_markdown)),
+        visible: details
+            .is_archived
+            .map_or(bookmark.visible, |archived| !archived),
+    };
+    bookmark::update_bookmark(pool, user, bookmark_id, updated).await?;
+    if let Some(names) = &details.tag_names {
+        set_tags(pool, user, bookmark_id, names).await?;
+    }
+    find_bookmark_by_id(pool, user, bookmark_id).await
+}
+
+/// Creates a new bookmark in user's inbox, named after its page unless a title is sent.
+/// As with linkding, bookmark linking the same page gets updated instead, if there's one.
+/// Returns the bookmark along with whether it's been created.
+pub async fn create_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    details: LinkdingBookmarkDetails,
+) -> anyhow::Result<(LinkdingBookmark, bool)> {
+    let details = details.normalize_tags()?;
+    let url = details
+        .url
+        .clone()
+        .ok_or_else(|| RequestError::Invalid("url", "url is required".into()))?;
+    if let Some(existing) = duplicate::find_bookmark_duplicate(pool, user, &url).await? {
+        return Ok((update(pool, user, &existing.id, details).await?, false));
+    }
+
+    let name = match details.title() {
+        Some(title) => title.to_string(),
+        None => inbox::page_title(&url).await,
+    };
+    let new = BookmarkDetails {
+        category_id: inbox::ensure_inbox(pool, user).await?,
+        name,
+        url,
+        icon: None,
+        notes: details.notes().flatten(),
+        visible: !details.is_archived.unwrap_or_default(),
+    };
+    let created = bookmark::create_bookmark(pool, user, new).await?;
+    if let Some(names) = &details.tag_names {
+        set_tags(pool, user, &created.id, names).await?;
+    }
+    Ok((find_bookmark_by_id(pool, user, &created.id).await?, true))
+}
+
+pub async fn update_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    id: i64,
+    details: LinkdingBookmarkDetails,
+) -> anyhow::Result<LinkdingBookmark> {
+    let details = details.normalize_tags()?;
+    let bookmark = find_bookmark(pool, user, id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    update(pool, user, &bookmark.bookmark_id, details).await
+}
+
+/// Archives bookmark by hiding it, or unarchives it by showing it again.
+pub async fn set_archived(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    id: i64,
+    archived: bool,
+) -> anyhow::Result<()> {
+    let bookmark = find_bookmark(pool, user, id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    bookmark::set_bookmark_visibility(pool, user, &bookmark.bookmark_id, !archived).await?;
+    Ok(())
+}
+
+pub async fn delete_bookmark(pool: &Pool<Sqlite>, user: &User, id: i64) -> anyhow::Result<()> {
+    let bookmark = find_bookmark(pool, user, id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    bookmark::delete_bookmark(pool, user, &bookmark.bookmark_id).await
+}
+
+pub async fn fetch_tags(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<LinkdingTag>> {
+    Ok(
+        Linkding::fetch_tags_for_user_id::<_, LinkdingTag>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's tags");
+                InternalError::TagsFetch
+            })?,
+    )
+}
+
+pub async fn find_tag(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    id: i64,
+) -> anyhow::Result<Option<LinkdingTag>> {
+    Ok(
+        Linkding::fetch_tag_by_linkding_id::<_, LinkdingTag>(pool, params!(id, user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's tag");
+                InternalError::TagsFetch
+            })?,
+    )
+}
+
+/// Creates a new tag, or returns the existing one of the same name.
+pub async fn create_tag(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    name: &str,
+) -> anyhow::Result<LinkdingTag> {
+    let name = normalize_tag(name)?;
+    let mut conn = pool.acquire().await.map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't create new tag");
+        InternalError::TagsUpdate
+    })?;
+    let tag_id = tag::ensure_tag(&mut conn, user, &name).await?;
+    Ok(
+        Linkding::fetch_tag_by_id::<_, LinkdingTag>(&mut *conn, params!(tag_id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's tag");
+                InternalError::TagsFetch
+            })?,
+    )
+}
@@ -1,4 +1,16 @@
 pub mod application;
+pub mod archive;
 pub mod bookmark;
+pub mod bulk;
 pub mod category;
+pub mod duplicate;
+pub mod group;
+pub mod icon;
+pub mod link_check;
+pub mod linkding;
+pub mod search;
+pub mod tag;
+pub mod token;
+pub mod trash;
 pub mod user;
+pub mod visit;
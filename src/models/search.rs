@@ -0,0 +1,287 @@
+use hugsqlx::{params, HugSqlx};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::{
+    errors::{InternalError, RequestError},
+    formats, urls,
+};
+
+use super::{
+    application::{self, Application},
+    user::User,
+    visit::{self, SortOrder},
+};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/search.sql"]
+struct Search {}
+
+/// Markers of matched text, replaced by HTML tags once the text gets escaped.
+const MATCH_START: &str = "\u{1}";
+const MATCH_END: &str = "\u{2}";
+
+/// Matching fields as HTML, with matched terms wrapped in `<mark>` tags.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct Highlights {
+    #[sqlx(rename = "name_highlight")]
+    pub name: String,
+    #[sqlx(rename = "description_highlight")]
+    pub description: Option<String>,
+    #[sqlx(rename = "url_highlight")]
+    pub url: Option<String>,
+    /// Excerpt of notes around matched terms, as long as notes match.
+    #[sqlx(rename = "notes_highlight")]
+    pub notes: Option<String>,
+}
+
+/// Bookmark, application or category matching search query.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct SearchHit {
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    #[sqlx(flatten)]
+    pub highlights: Highlights,
+    /// bm25 rank of the hit, the lower the better.
+    pub rank: f64,
+    pub frecency: u32,
+}
+
+fn highlight(text: &str) -> String {
+    formats::escape(text)
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// Turns user's query into FTS5 one. Each word is matched as a prefix of indexed terms,
+/// so that results show up while the query is being typed.
+fn match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(|term| format!("\"{term}\"*"))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Searches user's bookmarks, applications and categories, the best matches first.
+/// Matches might be ordered by frecency instead, with the best ones first among equals.
+pub async fn search(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    query: &str,
+    limit: u32,
+    order: SortOrder,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let Some(query) = match_query(query) else {
+        return Ok(Vec::new());
+    };
+    let hits = Search::search_for_user_id::<_, SearchHit>(
+        pool,
+        params!(
+            query,
+            user.id,
+            MATCH_START,
+            MATCH_END,
+            limit,
+            order == SortOrder::Frecency
+        ),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "Couldn't search user's items");
+        InternalError::Search
+    })?;
+
+    Ok(hits
+        .into_iter()
+        .map(|mut hit| {
+            hit.highlights.name = highlight(&hit.highlights.name);
+            hit.highlights.description = hit.highlights.description.as_deref().map(highlight);
+            hit.highlights.url = hit.highlights.url.as_deref().map(highlight);
+            hit.highlights.notes = hit
+                .highlights
+                .notes
+                .as_deref()
+                .filter(|notes| notes.contains(MATCH_START))
+                .map(highlight);
+            hit
+        })
+        .collect())
+}
+
+/// Splits "app-name terms" query into the searchable application named by the longest
+/// matching prefix of query (case-insensitively) and the rest of the query.
+fn split_dispatched<'a>(
+    applications: &'a [Application],
+    query: &'a str,
+) -> Option<(&'a Application, &'a str)> {
+    let query = query.trim();
+    applications
+        .iter()
+        .filter(|application| application.searchable && application.search_url.is_some())
+        .filter_map(|application| {
+            let name = application.name.trim();
+            let prefix = query.get(..name.len())?;
+            let terms = &query[name.len()..];
+            (!name.is_empty()
+                && prefix.eq_ignore_ascii_case(name)
+                && (terms.is_empty() || terms.starts_with(char::is_whitespace)))
+            .then_some((application, terms.trim()))
+        })
+        .max_by_key(|(application, _)| application.name.trim().len())
+}
+
+/// Dispatches "app-name terms" query to the search of named application, returning URL
+/// searching for the terms within the application. Query with no terms leads to
+/// application itself. Dispatch is recorded as a visit of the application.
+pub async fn dispatch(pool: &Pool<Sqlite>, user: &User, query: &str) -> anyhow::Result<String> {
+    let applications = application::fetch_applications(pool, user, None).await?;
+    let (application, terms) = split_dispatched(&applications, query)
+        .ok_or(RequestError::NotFound("Searchable application"))?;
+
+    let url = visit::record_visit(pool, user, &application.id).await?;
+    Ok(match application.search_url.as_deref() {
+        Some(template) if !terms.is_empty() => urls::expand_search_url(template, terms),
+        _ => url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_words_as_prefixes() {
+        assert_eq!(
+            match_query("  rust  axum ").as_deref(),
+            Some(r#""rust"* "axum"*"#)
+        );
+    }
+
+    #[test]
+    fn neutralizes_fts5_syntax() {
+        assert_eq!(
+            match_query(r#"say "hi" NEAR(a b) -x ^y col:z w*"#).as_deref(),
+            Some(r#""say"* "hi"* "NEAR(a"* "b)"* "-x"* "^y"* "col:z"* "w*"*"#)
+        );
+        // operators are searched for as plain words, punctuation alone is no term at all
+        assert_eq!(match_query(r#"OR"#).as_deref(), Some(r#""OR"*"#));
+        assert_eq!(match_query(r#"" - * ( )"#), None);
+    }
+
+    #[tokio::test]
+    async fn runs_neutralized_queries() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE VIRTUAL TABLE items USING fts5(name)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO items(name) VALUES ('say hi near NEAR(a b)')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for query in [r#"say "hi"#, "NEAR(a b)", "-x OR", "col:z ^y", "a\"b*"] {
+            let query = match_query(query).unwrap();
+            sqlx::query("SELECT name FROM items WHERE items MATCH $1")
+                .bind(&query)
+                .fetch_all(&pool)
+                .await
+                .unwrap_or_else(|e| panic!("{query}: {e}"));
+        }
+    }
+
+    #[test]
+    fn makes_no_query_of_empty_one() {
+        assert_eq!(match_query(""), None);
+        assert_eq!(match_query(" \t\n"), None);
+    }
+
+    #[test]
+    fn escapes_highlighted_text() {
+        let text = format!("<script>alert('{MATCH_START}x{MATCH_END}')</script> & \"y\"");
+
+        assert_eq!(
+            highlight(&text),
+            "&lt;script&gt;alert(&#39;<mark>x</mark>&#39;)&lt;/script&gt; &amp; &quot;y&quot;"
+        );
+        assert_eq!(highlight("<mark>"), "&lt;mark&gt;");
+    }
+
+    fn application(name: &str, searchable: bool) -> Application {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "category_id": null,
+            "name": name,
+            "description": null,
+            "url": "https://example.com/",
+            "icon": null,
+            "visible": true,
+            "shared": false,
+            "searchable": searchable,
+            "search_url": "https://example.com/?q={q}",
+            "position": 0,
+        }))
+        .unwrap()
+    }
+
+    fn dispatched<'a>(
+        applications: &'a [Application],
+        query: &'a str,
+    ) -> Option<(&'a str, &'a str)> {
+        split_dispatched(applications, query)
+            .map(|(application, terms)| (application.name.as_str(), terms))
+    }
+
+    #[test]
+    fn dispatches_to_the_longest_matching_name() {
+        let applications = [
+            application("Grafana", true),
+            application("Grafana Cloud", true),
+        ];
+
+        assert_eq!(
+            dispatched(&applications, "grafana cloud alerts"),
+            Some(("Grafana Cloud", "alerts"))
+        );
+        assert_eq!(
+            dispatched(&applications, "grafana cloudy"),
+            Some(("Grafana", "cloudy"))
+        );
+        assert_eq!(dispatched(&applications, "grafanas cpu"), None);
+    }
+
+    #[test]
+    fn dispatches_case_insensitively() {
+        let applications = [application("GitHub", true)];
+
+        assert_eq!(
+            dispatched(&applications, "  GITHUB  rust axum "),
+            Some(("GitHub", "rust axum"))
+        );
+    }
+
+    #[test]
+    fn dispatches_name_without_terms() {
+        let applications = [application("GitHub", true)];
+
+        assert_eq!(dispatched(&applications, "github"), Some(("GitHub", "")));
+        assert_eq!(dispatched(&applications, "github  "), Some(("GitHub", "")));
+    }
+
+    #[test]
+    fn skips_unsearchable_applications() {
+        let mut without_search_url = application("Jira", true);
+        without_search_url.search_url = None;
+        let applications = [application("GitHub", false), without_search_url];
+
+        assert_eq!(dispatched(&applications, "github rust"), None);
+        assert_eq!(dispatched(&applications, "jira bug"), None);
+    }
+}
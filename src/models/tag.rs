@@ -0,0 +1,277 @@
+use hugsqlx::{params, HugSqlx};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection};
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::{application, bookmark, user::User};
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/tags.sql"]
+struct Tags {}
+
+#[derive(Serialize, Deserialize, Debug, sqlx::FromRow)]
+pub struct Tag {
+    #[sqlx(rename = "tag_id")]
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Tag along with number of bookmarks and applications it's been assigned to.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct TagSummary {
+    #[sqlx(rename = "tag_id")]
+    pub id: Uuid,
+    pub name: String,
+    pub bookmarks: u32,
+    pub applications: u32,
+}
+
+/// Normalizes tag name. Tags are case-insensitive single words, like `oncall` or `prod`.
+pub fn normalize_tag(name: &str) -> Result<String, RequestError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.len() > 64 || name.chars().any(char::is_whitespace) {
+        return Err(RequestError::Invalid(
+            "tag",
+            format!("'{name}' (tag has to be a single word up to 64 characters)"),
+        ));
+    }
+    Ok(name)
+}
+
+pub async fn fetch_tags(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<TagSummary>> {
+    Ok(
+        Tags::fetch_tags_for_user_id::<_, TagSummary>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's tags");
+                InternalError::TagsFetch
+            })?,
+    )
+}
+
+pub async fn fetch_bookmark_tags(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<Vec<Tag>> {
+    Ok(
+        Tags::fetch_tags_for_bookmark_id::<_, Tag>(pool, params!(bookmark_id, user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load bookmark's tags");
+                InternalError::TagsFetch
+            })?,
+    )
+}
+
+pub async fn fetch_application_tags(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<Vec<Tag>> {
+    Ok(
+        Tags::fetch_tags_for_application_id::<_, Tag>(pool, params!(application_id, user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load application's tags");
+                InternalError::TagsFetch
+            })?,
+    )
+}
+
+fn update_error(e: sqlx::Error) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't update tags");
+    InternalError::TagsUpdate
+}
+
+/// Returns identifier of user's tag with given name. Tag gets created if it doesn't exist yet.
+pub async fn ensure_tag(
+    conn: &mut SqliteConnection,
+    user: &User,
+    name: &str,
+) -> anyhow::Result<Uuid> {
+    Ok(
+        Tags::upsert_tag(conn, params!(Uuid::new_v4(), user.id, name))
+            .await
+            .map(|row| row.get(0))
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't create new tag");
+                InternalError::TagsUpdate
+            })?,
+    )
+}
+
+pub async fn delete_tag(pool: &Pool<Sqlite>, user: &User, name: &str) -> anyhow::Result<()> {
+    let name = normalize_tag(name)?;
+    let result = Tags::delete_tag(pool, params!(user.id, &name))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't delete tag");
+            InternalError::TagsUpdate
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Tag").into());
+    }
+    Ok(())
+}
+
+pub async fn tag_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<Vec<Tag>> {
+    tag_bookmark_in(
+        &mut *pool.acquire().await.map_err(update_error)?,
+        user,
+        bookmark_id,
+        name,
+    )
+    .await?;
+    fetch_bookmark_tags(pool, user, bookmark_id).await
+}
+
+/// Tags user's bookmark using given connection, like the one of a transaction.
+pub async fn tag_bookmark_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    bookmark_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<()> {
+    let name = normalize_tag(name)?;
+    if bookmark::find_bookmark_in(conn, user, bookmark_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    let tag_id = ensure_tag(conn, user, &name).await?;
+    Tags::tag_bookmark(conn, params!(bookmark_id, tag_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't tag bookmark");
+            InternalError::TagsUpdate
+        })?;
+    Ok(())
+}
+
+pub async fn untag_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<Vec<Tag>> {
+    untag_bookmark_in(
+        &mut *pool.acquire().await.map_err(update_error)?,
+        user,
+        bookmark_id,
+        name,
+    )
+    .await?;
+    fetch_bookmark_tags(pool, user, bookmark_id).await
+}
+
+/// Removes tag from user's bookmark using given connection, like the one of a transaction.
+pub async fn untag_bookmark_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    bookmark_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<()> {
+    let name = normalize_tag(name)?;
+    if bookmark::find_bookmark_in(conn, user, bookmark_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    Tags::untag_bookmark(conn, params!(bookmark_id, user.id, &name))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't untag bookmark");
+            InternalError::TagsUpdate
+        })?;
+    Ok(())
+}
+
+pub async fn tag_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<Vec<Tag>> {
+    tag_application_in(
+        &mut *pool.acquire().await.map_err(update_error)?,
+        user,
+        application_id,
+        name,
+    )
+    .await?;
+    fetch_application_tags(pool, user, application_id).await
+}
+
+/// Tags user's application using given connection, like the one of a transaction.
+pub async fn tag_application_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    application_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<()> {
+    let name = normalize_tag(name)?;
+    if application::find_own_application_in(conn, user, application_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    let tag_id = ensure_tag(conn, user, &name).await?;
+    Tags::tag_application(conn, params!(application_id, tag_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't tag application");
+            InternalError::TagsUpdate
+        })?;
+    Ok(())
+}
+
+pub async fn untag_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<Vec<Tag>> {
+    untag_application_in(
+        &mut *pool.acquire().await.map_err(update_error)?,
+        user,
+        application_id,
+        name,
+    )
+    .await?;
+    fetch_application_tags(pool, user, application_id).await
+}
+
+/// Removes tag from user's application using given connection, like the one of a transaction.
+pub async fn untag_application_in(
+    conn: &mut SqliteConnection,
+    user: &User,
+    application_id: &Uuid,
+    name: &str,
+) -> anyhow::Result<()> {
+    let name = normalize_tag(name)?;
+    if application::find_own_application_in(conn, user, application_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    Tags::untag_application(conn, params!(application_id, user.id, &name))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't untag application");
+            InternalError::TagsUpdate
+        })?;
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use hugsqlx::{params, HugSqlx};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+use std::fmt::Debug;
+use subtle_encoding::hex;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::user::User;
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/tokens.sql"]
+struct Tokens {}
+
+/// Prefix of API tokens' secrets, telling them apart from other credentials.
+const SECRET_PREFIX: &str = "trufel_";
+
+/// What API token lets its bearer do.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Everything user can do through the API.
+    #[default]
+    Api,
+
+    /// Only searching user's bookmarks and applications, as browsers do with
+    /// address-bar queries. Such tokens end up in browsers' settings and history.
+    Search,
+}
+
+/// API token letting scripts, bookmarklets and other apps act on behalf of its user.
+/// Only a hash of token's secret is stored.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct ApiToken {
+    #[sqlx(rename = "token_id")]
+    pub id: Uuid,
+    pub name: String,
+    pub scope: TokenScope,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+}
+
+/// Newly created API token, along with its secret which is never revealed again.
+#[derive(Serialize, Debug)]
+pub struct NewApiToken {
+    #[serde(flatten)]
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiTokenDetails {
+    pub name: String,
+    #[serde(default)]
+    pub scope: TokenScope,
+}
+
+fn token_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't update API tokens");
+    InternalError::TokensUpdate
+}
+
+fn hash_secret(secret: &str) -> String {
+    String::from_utf8(hex::encode(Sha256::digest(secret.as_bytes())))
+        .expect("Hex encoding is not valid UTF-8")
+}
+
+pub async fn fetch_tokens(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<ApiToken>> {
+    Ok(
+        Tokens::fetch_tokens_for_user_id::<_, ApiToken>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's API tokens");
+                InternalError::TokensFetch
+            })?,
+    )
+}
+
+/// Creates a new API token with a random secret.
+pub async fn create_token(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    details: ApiTokenDetails,
+) -> anyhow::Result<NewApiToken> {
+    let name = details.name.trim();
+    if name.is_empty() {
+        return Err(RequestError::Invalid("name", "name cannot be empty".into()).into());
+    }
+    let secret = format!(
+        "{SECRET_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    );
+    let token_id = Uuid::new_v4();
+
+    Tokens::create_token(
+        pool,
+        params!(token_id, user.id, name, details.scope, hash_secret(&secret)),
+    )
+    .await
+    .map_err(token_error)?;
+    let token = Tokens::fetch_token_by_id::<_, ApiToken>(pool, params!(token_id, user.id))
+        .await
+        .map_err(token_error)?
+        .ok_or(InternalError::TokensUpdate)?;
+    Ok(NewApiToken { token, secret })
+}
+
+pub async fn delete_token(pool: &Pool<Sqlite>, user: &User, token_id: &Uuid) -> anyhow::Result<()> {
+    let result = Tokens::delete_token(pool, params!(token_id, user.id))
+        .await
+        .map_err(token_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("API token").into());
+    }
+    Ok(())
+}
+
+/// Finds owner of API token with given secret and scope, and records that the token got used.
+pub async fn find_by_secret(
+    pool: &Pool<Sqlite>,
+    secret: &str,
+    scope: TokenScope,
+) -> anyhow::Result<Option<User>> {
+    if !secret.starts_with(SECRET_PREFIX) {
+        return Ok(None);
+    }
+    let hash = hash_secret(secret);
+    let user = Tokens::fetch_user_by_token_hash::<_, User>(pool, params!(&hash, scope))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't load API token's user");
+            InternalError::TokensFetch
+        })?;
+    if user.is_some() {
+        Tokens::update_token_usage(pool, params!(&hash))
+            .await
+            .map_err(token_error)?;
+    }
+    Ok(user)
+}
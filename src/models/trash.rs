@@ -0,0 +1,179 @@
+use hugsqlx::{params, HugSqlx};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use std::fmt::Debug;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::user::User;
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/trash.sql"]
+struct Trash {}
+
+/// Trashed category, bookmark or application.
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct TrashedItem {
+    pub kind: String,
+    pub id: Uuid,
+    pub name: String,
+    pub url: Option<String>,
+    pub category_id: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub deleted_at: OffsetDateTime,
+}
+
+fn trash_error<E: Debug>(e: E) -> InternalError {
+    tracing::error!(error = ?e, "Couldn't update trash");
+    InternalError::TrashUpdate
+}
+
+pub async fn fetch_trash(pool: &Pool<Sqlite>, user: &User) -> anyhow::Result<Vec<TrashedItem>> {
+    Ok(
+        Trash::fetch_trash_for_user_id::<_, TrashedItem>(pool, params!(user.id))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "Couldn't load user's trash");
+                InternalError::TrashFetch
+            })?,
+    )
+}
+
+/// Restores user's category along with bookmarks trashed together with it.
+pub async fn restore_category(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_id: &Uuid,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await.map_err(trash_error)?;
+    Trash::restore_category_bookmarks(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    let result = Trash::restore_category(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Category").into());
+    }
+    tx.commit().await.map_err(trash_error)?;
+    Ok(())
+}
+
+/// Restores user's bookmark. Trashed category of the bookmark gets restored too,
+/// though without its other bookmarks.
+pub async fn restore_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await.map_err(trash_error)?;
+    Trash::restore_bookmark_category(&mut *tx, params!(bookmark_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    let result = Trash::restore_bookmark(&mut *tx, params!(bookmark_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    tx.commit().await.map_err(trash_error)?;
+    Ok(())
+}
+
+pub async fn restore_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Trash::restore_application(pool, params!(application_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    Ok(())
+}
+
+/// Permanently deletes user's trashed category along with its bookmarks. Applications
+/// grouped in the category are kept, not grouped in any category anymore.
+pub async fn purge_category(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    category_id: &Uuid,
+) -> anyhow::Result<()> {
+    let mut tx = pool.begin().await.map_err(trash_error)?;
+    Trash::purge_category_bookmarks(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    Trash::ungroup_category_applications(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    let result = Trash::purge_category(&mut *tx, params!(category_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Category").into());
+    }
+    tx.commit().await.map_err(trash_error)?;
+    Ok(())
+}
+
+pub async fn purge_bookmark(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    bookmark_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Trash::purge_bookmark(pool, params!(bookmark_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    Ok(())
+}
+
+pub async fn purge_application(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    application_id: &Uuid,
+) -> anyhow::Result<()> {
+    let result = Trash::purge_application(pool, params!(application_id, user.id))
+        .await
+        .map_err(trash_error)?;
+    if result.rows_affected() == 0 {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    Ok(())
+}
+
+/// Permanently deletes items trashed at least `days` ago, optionally only the user's ones.
+/// Returns number of purged items.
+pub async fn purge_trash(
+    pool: &Pool<Sqlite>,
+    user: Option<&User>,
+    days: u32,
+) -> anyhow::Result<u64> {
+    let user_id = user.map(|user| user.id);
+    let mut tx = pool.begin().await.map_err(trash_error)?;
+
+    let mut purged = Trash::purge_bookmarks(&mut *tx, params!(user_id, days))
+        .await
+        .map_err(trash_error)?
+        .rows_affected();
+    purged += Trash::purge_applications(&mut *tx, params!(user_id, days))
+        .await
+        .map_err(trash_error)?
+        .rows_affected();
+    Trash::ungroup_applications(&mut *tx, params!(user_id, days))
+        .await
+        .map_err(trash_error)?;
+    purged += Trash::purge_categories(&mut *tx, params!(user_id, days))
+        .await
+        .map_err(trash_error)?
+        .rows_affected();
+
+    tx.commit().await.map_err(trash_error)?;
+    Ok(purged)
+}
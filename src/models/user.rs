@@ -5,7 +5,10 @@ use sqlx::{Pool, Sqlite};
 use std::str::FromStr;
 use uuid::Uuid;
 
-use crate::{errors::InternalError, jwt::Claims};
+use crate::{
+    errors::{InternalError, RequestError},
+    jwt::Claims,
+};
 
 #[derive(HugSqlx)]
 #[queries = "resources/db/queries/users.sql"]
@@ -18,6 +21,20 @@ pub struct User {
     pub email: String,
     pub name: String,
     pub picture: Option<String>,
+    /// Admins manage applications shared with others. Admin rights are granted from
+    /// command line only, never taken from user's profile.
+    #[serde(default, skip_deserializing)]
+    pub admin: bool,
+}
+
+impl User {
+    /// Fails unless user is an admin, allowed to perform given action.
+    pub fn ensure_admin(&self, action: &'static str) -> Result<(), RequestError> {
+        match self.admin {
+            true => Ok(()),
+            false => Err(RequestError::AdminOnly(action)),
+        }
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -36,6 +53,20 @@ pub async fn find_by_user_id(pool: &Pool<Sqlite>, user_id: &Uuid) -> anyhow::Res
     Ok(user)
 }
 
+pub async fn find_by_email(pool: &Pool<Sqlite>, email: &str) -> anyhow::Result<Option<User>> {
+    let user = DbUsers::fetch_user_by_email::<_, User>(pool, params!(email.to_lowercase())).await?;
+    Ok(user)
+}
+
+/// Grants or revokes admin rights of user with given email.
+pub async fn set_admin(pool: &Pool<Sqlite>, email: &str, admin: bool) -> anyhow::Result<()> {
+    let result = DbUsers::update_user_admin(pool, params!(email.to_lowercase(), admin)).await?;
+    if result.rows_affected() == 0 {
+        bail!("No user found with email {email}");
+    }
+    Ok(())
+}
+
 #[tracing::instrument(skip(pool, user))]
 pub async fn store(pool: &Pool<Sqlite>, user: User) -> anyhow::Result<User> {
     let uid = user.id;
@@ -0,0 +1,52 @@
+use hugsqlx::{params, HugSqlx};
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::errors::{InternalError, RequestError};
+
+use super::user::User;
+
+#[derive(HugSqlx)]
+#[queries = "resources/db/queries/visits.sql"]
+struct Visits {}
+
+/// Order of listed bookmarks, applications or search results.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Order set up by user, or by relevance for search results.
+    #[default]
+    Position,
+
+    /// The most frequently and recently visited first.
+    Frecency,
+}
+
+#[derive(sqlx::FromRow)]
+struct ItemUrl {
+    url: String,
+}
+
+/// Records user's visit of bookmark or application and returns URL of the visited item.
+pub async fn record_visit(
+    pool: &Pool<Sqlite>,
+    user: &User,
+    item_id: &Uuid,
+) -> anyhow::Result<String> {
+    let item = Visits::fetch_item_url::<_, ItemUrl>(pool, params!(item_id, user.id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't load visited item");
+            InternalError::LinksFetch
+        })?
+        .ok_or(RequestError::NotFound("Bookmark or application"))?;
+
+    Visits::create_visit(pool, params!(user.id, item_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "Couldn't record visit");
+            InternalError::VisitsCreate
+        })?;
+    Ok(item.url)
+}
@@ -0,0 +1,154 @@
+//! Markdown notes attached to bookmarks and applications, like "use VPN" or where to find
+//! credentials. Notes are stored as Markdown and rendered into sanitized HTML whenever
+//! they're sent to the client, so that no script or style ever gets through.
+
+use pulldown_cmark::{html, Options, Parser};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::RequestError;
+
+/// Maximal length of notes, in bytes.
+const MAX_LENGTH: usize = 64 * 1024;
+
+/// Markdown notes, serialized along with their rendered HTML.
+#[derive(Debug, Clone, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Notes(String);
+
+impl From<String> for Notes {
+    fn from(markdown: String) -> Self {
+        Notes(markdown)
+    }
+}
+
+impl Notes {
+    pub fn into_markdown(self) -> String {
+        self.0
+    }
+
+    /// Renders notes as HTML, keeping only harmless tags and attributes.
+    pub fn html(&self) -> String {
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_FOOTNOTES;
+        let mut rendered = String::new();
+        html::push_html(&mut rendered, Parser::new_ext(&self.0, options));
+
+        ammonia::Builder::default()
+            .link_rel(Some("noopener noreferrer nofollow"))
+            .url_schemes(["http", "https", "mailto"].into())
+            .clean(&rendered)
+            .to_string()
+    }
+}
+
+/// Validates notes provided by user. Blank notes are no notes at all.
+pub fn validate_notes(notes: Option<String>) -> Result<Option<String>, RequestError> {
+    let Some(notes) = notes
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+    else {
+        return Ok(None);
+    };
+    if notes.len() > MAX_LENGTH {
+        return Err(RequestError::Invalid(
+            "notes",
+            format!("notes cannot be longer than {MAX_LENGTH} bytes"),
+        ));
+    }
+    Ok(Some(notes))
+}
+
+impl Serialize for Notes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut notes = serializer.serialize_struct("Notes", 2)?;
+        notes.serialize_field("markdown", &self.0)?;
+        notes.serialize_field("html", &self.html())?;
+        notes.end()
+    }
+}
+
+/// Notes are read back either as serialized (like from backups) or as plain Markdown.
+impl<'de> Deserialize<'de> for Notes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Markdown(String),
+            Serialized { markdown: String },
+        }
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Markdown(markdown) | Repr::Serialized { markdown } => Notes(markdown),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html(markdown: &str) -> String {
+        Notes::from(markdown.to_string()).html()
+    }
+
+    #[test]
+    fn renders_markdown() {
+        assert_eq!(
+            html("Use **VPN**, see [wiki](https://wiki.local)"),
+            "<p>Use <strong>VPN</strong>, see <a href=\"https://wiki.local\" rel=\"noopener noreferrer nofollow\">wiki</a></p>\n"
+        );
+        assert!(html("| a |\n|---|\n| b |").contains("<td>b</td>"));
+        assert!(html("~~old~~").contains("<del>old</del>"));
+    }
+
+    #[test]
+    fn strips_scripts_and_styles() {
+        let rendered = html(
+            "<script>alert(1)</script>\n\n<style>p { color: red }</style>\n\n\
+             <img src=\"https://example.com/a.png\" onerror=\"alert(1)\">\n\n\
+             <p style=\"position: fixed\" onclick=\"alert(1)\">hi</p>",
+        );
+        assert!(!rendered.contains("script"), "{rendered}");
+        assert!(!rendered.contains("style"), "{rendered}");
+        assert!(!rendered.contains("onerror"), "{rendered}");
+        assert!(!rendered.contains("onclick"), "{rendered}");
+        assert!(rendered.contains("<img src=\"https://example.com/a.png\">"));
+        assert!(rendered.contains("hi"));
+    }
+
+    #[test]
+    fn drops_unsafe_link_schemes() {
+        let rendered =
+            html("[a](javascript:alert(1)) [b](data:text/html,x) [c](mailto:ops@example.com)");
+        assert!(!rendered.contains("javascript"), "{rendered}");
+        assert!(!rendered.contains("data:"), "{rendered}");
+        assert!(rendered.contains("href=\"mailto:ops@example.com\""));
+    }
+
+    #[test]
+    fn validates_notes() {
+        assert_eq!(validate_notes(None).unwrap(), None);
+        assert_eq!(validate_notes(Some(" \n ".into())).unwrap(), None);
+        assert_eq!(
+            validate_notes(Some(" use VPN\n".into()))
+                .unwrap()
+                .as_deref(),
+            Some("use VPN")
+        );
+        assert!(validate_notes(Some("x".repeat(MAX_LENGTH + 1))).is_err());
+    }
+
+    #[test]
+    fn reads_serialized_and_plain_notes() {
+        let notes = Notes::from("**hi**".to_string());
+        let serialized = serde_json::to_value(&notes).unwrap();
+        assert_eq!(serialized["html"], "<p><strong>hi</strong></p>\n");
+
+        assert_eq!(serde_json::from_value::<Notes>(serialized).unwrap(), notes);
+        assert_eq!(
+            serde_json::from_value::<Notes>(serde_json::json!("**hi**")).unwrap(),
+            notes
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! OpenSearch integration, letting trufel be browser's address-bar search engine.
+//!
+//! Queries starting with a keyword of one of user's applications (like `gh rust axum`)
+//! search within that application, any other query leads to trufel's own search results.
+//! Browsers don't send user's session along with address-bar queries, so the description
+//! might be requested with a search-scoped API token, which then becomes a part of its URL
+//! templates. Such tokens can't do anything but search and can be revoked at any time.
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::{
+    formats::escape,
+    models::{
+        application::{self, Application},
+        search,
+        user::User,
+        visit::{self, SortOrder},
+    },
+    urls,
+};
+
+/// Path of webapp's page listing search results, as served by `/search` endpoint.
+const SEARCH_PAGE: &str = "/authenticated/search";
+
+/// Number of suggestions offered while the query is being typed.
+const SUGGESTIONS_LIMIT: u32 = 10;
+
+/// Suggestions in OpenSearch suggestions format - the query followed by completions,
+/// their descriptions and URLs.
+#[derive(Serialize, Debug)]
+pub struct Suggestions(String, Vec<String>, Vec<String>, Vec<String>);
+
+impl Suggestions {
+    fn push(&mut self, completion: String, description: String, url: String) {
+        if !self.1.contains(&completion) {
+            self.1.push(completion);
+            self.2.push(description);
+            self.3.push(url);
+        }
+    }
+}
+
+/// OpenSearch description document of trufel served at given public URL. The token, if
+/// any, is expected to be a search-scoped one.
+pub fn description(public_url: &str, token: Option<&str>) -> String {
+    let base = public_url.trim_end_matches('/');
+    let token = token
+        .map(|token| format!("&token={}", urls::encode_component(token)))
+        .unwrap_or_default();
+    let search = escape(&format!("{base}/opensearch?q={{searchTerms}}{token}"));
+    let suggestions = escape(&format!(
+        "{base}/opensearch/suggestions?q={{searchTerms}}{token}"
+    ));
+    let base = escape(base);
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/" xmlns:moz="http://www.mozilla.org/2006/browser/search/">
+  <ShortName>trufel</ShortName>
+  <Description>Search trufel's applications and bookmarks</Description>
+  <InputEncoding>UTF-8</InputEncoding>
+  <Image width="16" height="16" type="image/png">{base}/favicon.png</Image>
+  <Url type="text/html" method="get" template="{search}"/>
+  <Url type="application/x-suggestions+json" method="get" template="{suggestions}"/>
+  <moz:SearchForm>{base}/</moz:SearchForm>
+</OpenSearchDescription>
+"#
+    )
+}
+
+/// Splits query into application named by its first word as a keyword and the rest of
+/// query. User's own applications take precedence over the ones shared with them.
+fn split_keyword<'a>(
+    applications: &'a [Application],
+    query: &'a str,
+) -> Option<(&'a Application, &'a str)> {
+    let query = query.trim();
+    let (keyword, terms) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    let keyword = keyword.to_lowercase();
+    applications
+        .iter()
+        .filter(|application| application.keyword.as_deref() == Some(keyword.as_str()))
+        .min_by_key(|application| application.managed)
+        .map(|application| (application, terms.trim()))
+}
+
+/// URL of trufel's search results page of given query.
+fn search_page(public_url: &str, query: &str) -> String {
+    format!(
+        "{}{SEARCH_PAGE}?q={}",
+        public_url.trim_end_matches('/'),
+        urls::encode_component(query.trim())
+    )
+}
+
+/// Resolves address-bar query into URL to go to. Keyword queries search within their
+/// applications (or lead to the application itself when there's nothing to search for)
+/// and are recorded as visits of these applications, any other query leads to trufel's
+/// search results page.
+pub async fn resolve(
+    pool: &SqlitePool,
+    user: &User,
+    query: &str,
+    public_url: &str,
+) -> anyhow::Result<String> {
+    let applications = application::fetch_applications(pool, user, None).await?;
+    if let Some((application, terms)) = split_keyword(&applications, query) {
+        let url = visit::record_visit(pool, user, &application.id).await?;
+        return Ok(match application.search_url.as_deref() {
+            Some(template) if !terms.is_empty() => urls::expand_search_url(template, terms),
+            _ => url,
+        });
+    }
+
+    Ok(search_page(public_url, query))
+}
+
+/// Suggests completions of address-bar query - keywords of applications and bookmarks
+/// matching the query.
+pub async fn suggest(pool: &SqlitePool, user: &User, query: &str) -> anyhow::Result<Suggestions> {
+    let mut suggestions = Suggestions(query.to_string(), Vec::new(), Vec::new(), Vec::new());
+    let applications = application::fetch_applications(pool, user, None).await?;
+
+    match split_keyword(&applications, query) {
+        Some((application, terms)) if !terms.is_empty() => {
+            if let Some(template) = &application.search_url {
+                suggestions.push(
+                    query.trim().to_string(),
+                    format!("Search {}", application.name),
+                    urls::expand_search_url(template, terms),
+                );
+            }
+        }
+        _ => {
+            let prefix = query.trim().to_lowercase();
+            for application in applications.iter().filter(|a| a.visible) {
+                if let Some(keyword) = &application.keyword {
+                    if !prefix.is_empty() && keyword.starts_with(&prefix) {
+                        suggestions.push(
+                            format!("{keyword} "),
+                            application.name.clone(),
+                            application.url.clone(),
+                        );
+                    }
+                }
+            }
+            let hits =
+                search::search(pool, user, query, SUGGESTIONS_LIMIT, SortOrder::Frecency).await?;
+            for hit in hits.into_iter().filter(|hit| hit.kind == "bookmark") {
+                if let Some(url) = hit.url {
+                    suggestions.push(hit.name, hit.description.unwrap_or_default(), url);
+                }
+            }
+        }
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn application(name: &str, keyword: &str, managed: bool) -> Application {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "category_id": null,
+            "name": name,
+            "description": null,
+            "url": "https://example.com/",
+            "icon": null,
+            "visible": true,
+            "shared": managed,
+            "searchable": true,
+            "search_url": "https://example.com/?q={q}",
+            "keyword": keyword,
+            "position": 0,
+            "managed": managed,
+        }))
+        .unwrap()
+    }
+
+    fn split<'a>(applications: &'a [Application], query: &'a str) -> Option<(&'a str, &'a str)> {
+        split_keyword(applications, query)
+            .map(|(application, terms)| (application.name.as_str(), terms))
+    }
+
+    #[test]
+    fn splits_keyword_and_query() {
+        let applications = [
+            application("GitHub", "gh", false),
+            application("Grafana", "gf", false),
+        ];
+
+        assert_eq!(
+            split(&applications, " GH  rust axum "),
+            Some(("GitHub", "rust axum"))
+        );
+        assert_eq!(split(&applications, "gf\tcpu"), Some(("Grafana", "cpu")));
+    }
+
+    #[test]
+    fn splits_keyword_without_query() {
+        let applications = [application("GitHub", "gh", false)];
+
+        assert_eq!(split(&applications, "gh"), Some(("GitHub", "")));
+        assert_eq!(split(&applications, "gh "), Some(("GitHub", "")));
+    }
+
+    #[test]
+    fn prefers_own_applications() {
+        let applications = [
+            application("Shared GitHub", "gh", true),
+            application("GitHub", "gh", false),
+        ];
+
+        assert_eq!(split(&applications, "gh rust"), Some(("GitHub", "rust")));
+    }
+
+    #[test]
+    fn leads_unknown_keywords_to_search_results() {
+        let applications = [application("GitHub", "gh", false)];
+
+        assert_eq!(split(&applications, "ghost stories"), None);
+        assert_eq!(split(&applications, "rust gh"), None);
+        assert_eq!(
+            search_page("https://trufel.local/", " ghost & stories "),
+            "https://trufel.local/authenticated/search?q=ghost%20%26%20stories"
+        );
+    }
+
+    #[test]
+    fn escapes_description_urls() {
+        let description = description("https://trufel.local/a&b/", Some("secret token&x"));
+
+        assert!(description.contains(
+            r#"<Url type="text/html" method="get" template="https://trufel.local/a&amp;b/opensearch?q={searchTerms}&amp;token=secret%20token%26x"/>"#
+        ));
+        assert!(description.contains(
+            r#"template="https://trufel.local/a&amp;b/opensearch/suggestions?q={searchTerms}&amp;token=secret%20token%26x"/>"#
+        ));
+        assert!(
+            description.contains("<moz:SearchForm>https://trufel.local/a&amp;b/</moz:SearchForm>")
+        );
+    }
+
+    #[test]
+    fn leaves_token_out_of_description_without_one() {
+        let description = description("https://trufel.local", None);
+
+        assert!(
+            description.contains(r#"template="https://trufel.local/opensearch?q={searchTerms}"/>"#)
+        );
+        assert!(!description.contains("token"));
+    }
+}
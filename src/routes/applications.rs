@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use uuid::Uuid;
+
+use crate::{
+    errors::{RequestError, ServiceError},
+    favicons::{self, IconTarget},
+    models::{
+        application::{self, Application, ApplicationDetails, ApplicationOverlay},
+        duplicate,
+        user::User,
+        visit::SortOrder,
+    },
+    routes::{duplicates::DuplicateFilter, tags::TagFilter, visits::SortFilter},
+};
+
+pub async fn applications(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(filter): Query<TagFilter>,
+    Query(order): Query<SortFilter>,
+) -> Result<Json<Vec<Application>>, ServiceError> {
+    let mut applications =
+        application::fetch_applications(&pool, &user, filter.tag.as_deref()).await?;
+    if order.sort == SortOrder::Frecency {
+        applications.sort_by_key(|item| Reverse(item.frecency));
+    }
+    Ok(Json(applications))
+}
+
+pub async fn get_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Application>, ServiceError> {
+    let application = application::find_application(&pool, &user, &application_id)
+        .await?
+        .ok_or(RequestError::NotFound("Application"))?;
+    Ok(Json(application))
+}
+
+pub async fn add_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(filter): Query<DuplicateFilter>,
+    Json(details): Json<ApplicationDetails>,
+) -> Result<Json<Application>, ServiceError> {
+    tracing::info!(url = details.url, "Adding new application");
+    if !filter.allow_duplicate {
+        if let Some(existing) =
+            duplicate::find_application_duplicate(&pool, &user, &details.url).await?
+        {
+            return Err(RequestError::Conflict("application", existing.url).into());
+        }
+    }
+    let application = application::create_application(&pool, &user, details).await?;
+
+    favicons::spawn_favicon_discovery(
+        pool,
+        IconTarget::Application(application.id),
+        application.url.clone(),
+    );
+    Ok(Json(application))
+}
+
+pub async fn update_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+    Json(details): Json<ApplicationDetails>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        application::update_application(&pool, &user, &application_id, details).await?,
+    ))
+}
+
+pub async fn delete_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    application::delete_application(&pool, &user, &application_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Hides or reorders application shared with user.
+pub async fn set_application_overlay(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+    Json(overlay): Json<ApplicationOverlay>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        application::set_overlay(&pool, &user, &application_id, overlay).await?,
+    ))
+}
+
+pub async fn reset_application_overlay(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        application::reset_overlay(&pool, &user, &application_id).await?,
+    ))
+}
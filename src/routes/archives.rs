@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{headers::LastModified, TypedHeader};
+use sqlx::SqlitePool;
+use std::time::SystemTime;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+use crate::{
+    archiver,
+    errors::{RequestError, ServiceError},
+    models::{
+        archive::{self, ArchiveInfo},
+        user::User,
+    },
+};
+
+/// Archived pages are not supposed to load anything but their inlined resources, nor to
+/// run any scripts that might have slipped through.
+const ARCHIVE_CSP: &str = "default-src 'none'; img-src data:; style-src 'unsafe-inline' data:; \
+                           font-src data:; media-src data:; form-action 'none'; sandbox";
+
+/// Captures snapshot of bookmarked page.
+pub async fn archive_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Json<ArchiveInfo>, ServiceError> {
+    Ok(Json(
+        archiver::archive_bookmark(&pool, &user, &bookmark_id).await?,
+    ))
+}
+
+/// Serves snapshot of bookmarked page, along with time of its capture.
+pub async fn get_archive(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Response, ServiceError> {
+    let archive = archive::find_archive(&pool, &user, &bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Archive"))?;
+
+    Ok((
+        TypedHeader(LastModified::from(SystemTime::from(archive.captured_at))),
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+            (
+                HeaderName::from_static("x-archived-at"),
+                archive.captured_at.format(&Rfc3339)?,
+            ),
+            (header::CONTENT_SECURITY_POLICY, ARCHIVE_CSP.to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        archive.html()?,
+    )
+        .into_response())
+}
+
+pub async fn delete_archive(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    archive::delete_archive(&pool, &user, &bookmark_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use uuid::Uuid;
+
+use crate::{
+    errors::{RequestError, ServiceError},
+    favicons::{self, IconTarget},
+    models::{
+        bookmark::{self, Bookmark, BookmarkDetails},
+        duplicate,
+        user::User,
+        visit::SortOrder,
+    },
+    routes::{duplicates::DuplicateFilter, tags::TagFilter, visits::SortFilter},
+};
+
+#[derive(Deserialize)]
+pub struct VisibilityRequestPayload {
+    visible: bool,
+}
+
+pub async fn bookmarks(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(filter): Query<TagFilter>,
+    Query(order): Query<SortFilter>,
+) -> Result<Json<Vec<Bookmark>>, ServiceError> {
+    let mut bookmarks = bookmark::fetch_bookmarks(&pool, &user, filter.tag.as_deref()).await?;
+    if order.sort == SortOrder::Frecency {
+        bookmarks.sort_by_key(|item| Reverse(item.frecency));
+    }
+    Ok(Json(bookmarks))
+}
+
+pub async fn get_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    let bookmark = bookmark::find_bookmark(&pool, &user, &bookmark_id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    Ok(Json(bookmark))
+}
+
+pub async fn add_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(filter): Query<DuplicateFilter>,
+    Json(details): Json<BookmarkDetails>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    tracing::info!(url = details.url, "Adding new bookmark");
+    if !filter.allow_duplicate {
+        if let Some(existing) =
+            duplicate::find_bookmark_duplicate(&pool, &user, &details.url).await?
+        {
+            return Err(RequestError::Conflict("bookmark", existing.url).into());
+        }
+    }
+    let bookmark = bookmark::create_bookmark(&pool, &user, details).await?;
+
+    favicons::spawn_favicon_discovery(
+        pool,
+        IconTarget::Bookmark(bookmark.id),
+        bookmark.url.clone(),
+    );
+    Ok(Json(bookmark))
+}
+
+pub async fn update_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+    Json(details): Json<BookmarkDetails>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    Ok(Json(
+        bookmark::update_bookmark(&pool, &user, &bookmark_id, details).await?,
+    ))
+}
+
+pub async fn update_bookmark_visibility(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+    Json(payload): Json<VisibilityRequestPayload>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    Ok(Json(
+        bookmark::set_bookmark_visibility(&pool, &user, &bookmark_id, payload.visible).await?,
+    ))
+}
+
+pub async fn delete_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    bookmark::delete_bookmark(&pool, &user, &bookmark_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
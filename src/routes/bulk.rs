@@ -0,0 +1,41 @@
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        bulk::{self, BulkAction, BulkResult},
+        user::User,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct BulkRequestPayload {
+    ids: Vec<Uuid>,
+    #[serde(flatten)]
+    action: BulkAction,
+}
+
+pub async fn bulk_bookmarks(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Json(payload): Json<BulkRequestPayload>,
+) -> Result<Json<Vec<BulkResult>>, ServiceError> {
+    tracing::info!(action = ?payload.action, items = payload.ids.len(), "Bulk updating bookmarks");
+    Ok(Json(
+        bulk::bulk_bookmarks(&pool, &user, &payload.ids, payload.action).await?,
+    ))
+}
+
+pub async fn bulk_applications(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Json(payload): Json<BulkRequestPayload>,
+) -> Result<Json<Vec<BulkResult>>, ServiceError> {
+    tracing::info!(action = ?payload.action, items = payload.ids.len(), "Bulk updating applications");
+    Ok(Json(
+        bulk::bulk_applications(&pool, &user, &payload.ids, payload.action).await?,
+    ))
+}
@@ -1,11 +1,16 @@
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
 use serde::Deserialize;
 use sqlx::SqlitePool;
+use uuid::Uuid;
 
 use crate::{
     errors::ServiceError,
     models::{
-        category::{create_category, fetch_categories, Category},
+        category::{create_category, delete_category, fetch_categories, Category},
         user::User,
     },
 };
@@ -30,3 +35,12 @@ pub async fn add_category(
     let category = create_category(&pool, &user, category.name).await?;
     Ok(Json(category))
 }
+
+pub async fn remove_category(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(category_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    delete_category(&pool, &user, &category_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
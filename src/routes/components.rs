@@ -24,8 +24,8 @@ pub async fn fetch_components(
 ) -> Result<Json<Components>, ServiceError> {
     tracing::info!("Fetching user's bookmarks");
 
-    let applications = fetch_applications(&pool, &user).await?;
-    let links = fetch_bookmarks(&pool, &user).await?;
+    let applications = fetch_applications(&pool, &user, None).await?;
+    let links = fetch_bookmarks(&pool, &user, None).await?;
     let categories = fetch_categories(&pool, &user).await?;
 
     Ok(Json(Components {
@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        application::Application,
+        bookmark::Bookmark,
+        duplicate::{self, DuplicateGroup},
+        user::User,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct DuplicateFilter {
+    /// Creates item even if another one links the same page.
+    #[serde(default)]
+    pub allow_duplicate: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MergeRequestPayload {
+    ids: Vec<Uuid>,
+}
+
+pub async fn duplicates(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<Vec<DuplicateGroup>>, ServiceError> {
+    Ok(Json(duplicate::fetch_duplicates(&pool, &user).await?))
+}
+
+pub async fn merge_bookmarks(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+    Json(payload): Json<MergeRequestPayload>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    Ok(Json(
+        duplicate::merge_bookmarks(&pool, &user, &bookmark_id, &payload.ids).await?,
+    ))
+}
+
+pub async fn merge_applications(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+    Json(payload): Json<MergeRequestPayload>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        duplicate::merge_applications(&pool, &user, &application_id, &payload.ids).await?,
+    ))
+}
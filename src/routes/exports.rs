@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::{
+    backup,
+    errors::{RequestError, ServiceError},
+    exporter,
+    formats::{
+        csv::{self, CsvOptions},
+        dashy, heimdall, homer, netscape, xbel,
+    },
+    models::user::User,
+};
+
+#[derive(Deserialize)]
+pub struct ExportOptions {
+    #[serde(default)]
+    applications: bool,
+}
+
+/// Exports user's bookmarks as Netscape bookmark file, ready to be imported into browser.
+pub async fn export_netscape(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(options): Query<ExportOptions>,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting Netscape bookmarks");
+
+    let folders = exporter::export_folders(&pool, &user, options.applications).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/html; charset=UTF-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"bookmarks.html\"",
+            ),
+        ],
+        netscape::render(&folders),
+    ))
+}
+
+/// Exports user's bookmarks as XBEL document.
+pub async fn export_xbel(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(options): Query<ExportOptions>,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting XBEL bookmarks");
+
+    let folders = exporter::export_folders(&pool, &user, options.applications).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=UTF-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"bookmarks.xbel\"",
+            ),
+        ],
+        xbel::render(&folders),
+    ))
+}
+
+/// Exports user's bookmarks as CSV spreadsheet, with columns named according to
+/// query parameters.
+pub async fn export_csv(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(options): Query<ExportOptions>,
+    Query(csv_options): Query<CsvOptions>,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting CSV bookmarks");
+
+    let folders = exporter::export_folders(&pool, &user, options.applications).await?;
+    let content = csv::render(&folders, &csv_options)
+        .map_err(|e| RequestError::Invalid("CSV options", e.to_string()))?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=UTF-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"bookmarks.csv\"",
+            ),
+        ],
+        content,
+    ))
+}
+
+/// Exports user's applications as Homer's `config.yml`.
+pub async fn export_homer(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting Homer dashboard");
+
+    let applications = exporter::export_applications(&pool, &user).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/yaml; charset=UTF-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"config.yml\"",
+            ),
+        ],
+        homer::render(applications)?,
+    ))
+}
+
+/// Exports user's applications as Dashy's `conf.yml`.
+pub async fn export_dashy(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting Dashy dashboard");
+
+    let applications = exporter::export_applications(&pool, &user).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/yaml; charset=UTF-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"conf.yml\"",
+            ),
+        ],
+        dashy::render(applications)?,
+    ))
+}
+
+/// Exports user's applications as Heimdall's JSON array of items.
+pub async fn export_heimdall(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting Heimdall dashboard");
+
+    let applications = exporter::export_applications(&pool, &user).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"heimdall.json\"",
+            ),
+        ],
+        heimdall::render(applications)?,
+    ))
+}
+
+/// Exports complete user's dashboard as JSON backup document.
+pub async fn export_backup(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<impl IntoResponse, ServiceError> {
+    tracing::info!("Exporting backup");
+
+    let backup = backup::export_backup(&pool, &user).await?;
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"trufel-backup.json\"",
+        )],
+        Json(backup),
+    ))
+}
@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        group::{self, Group, GroupDetails},
+        user::User,
+    },
+};
+
+/// Lists all groups for admins, or the groups user is a member of for anyone else.
+pub async fn groups(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<Vec<Group>>, ServiceError> {
+    Ok(Json(group::fetch_groups(&pool, &user).await?))
+}
+
+pub async fn add_group(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Json(details): Json<GroupDetails>,
+) -> Result<(StatusCode, Json<Group>), ServiceError> {
+    let group = group::create_group(&pool, &user, details).await?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+pub async fn delete_group(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(group_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    group::delete_group(&pool, &user, &group_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_member(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((group_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Group>, ServiceError> {
+    Ok(Json(
+        group::add_member(&pool, &user, &group_id, &user_id).await?,
+    ))
+}
+
+pub async fn remove_member(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((group_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Group>, ServiceError> {
+    Ok(Json(
+        group::remove_member(&pool, &user, &group_id, &user_id).await?,
+    ))
+}
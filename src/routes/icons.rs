@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sqlx::SqlitePool;
+
+use crate::{
+    errors::{RequestError, ServiceError},
+    models::icon,
+};
+
+/// Serves stored icon. Icons are identified by hashes of their content, so they never
+/// change and might be cached forever.
+pub async fn get_icon(
+    State(pool): State<SqlitePool>,
+    Path(icon_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ServiceError> {
+    let etag = format!("\"{icon_id}\"");
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag))
+    {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    let icon = icon::find_icon(&pool, &icon_id)
+        .await?
+        .ok_or(RequestError::NotFound("Icon"))?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, icon.content_type),
+            (
+                header::CACHE_CONTROL,
+                "public, max-age=31536000, immutable".to_string(),
+            ),
+            (header::ETAG, format!("\"{}\"", icon.id)),
+            // icons might be SVGs, which must not run any scripts
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "default-src 'none'; style-src 'unsafe-inline'; sandbox".to_string(),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        icon.content,
+    )
+        .into_response())
+}
@@ -0,0 +1,153 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    backup::{self, Backup, RestoreMode},
+    errors::{RequestError, ServiceError},
+    formats::{
+        csv::{self, CsvOptions},
+        dashy, flame, heimdall, homer, netscape, xbel,
+    },
+    importer::{self, ImportReport},
+    models::user::User,
+};
+
+#[derive(Deserialize)]
+pub struct RestoreOptions {
+    #[serde(default)]
+    mode: RestoreMode,
+}
+
+/// Restores JSON backup document, either merging it into user's dashboard (`?mode=merge`,
+/// the default) or replacing the dashboard completely (`?mode=replace`).
+pub async fn import_backup(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(options): Query<RestoreOptions>,
+    Json(backup): Json<Backup>,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!(mode = ?options.mode, "Restoring backup");
+
+    Ok(Json(
+        backup::restore_backup(&pool, &user, backup, options.mode).await?,
+    ))
+}
+
+/// Imports bookmarks from Netscape bookmark file sent as request body.
+pub async fn import_netscape(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing Netscape bookmarks");
+
+    let folders = netscape::parse(&body);
+    Ok(Json(importer::import_folders(&pool, &user, folders).await?))
+}
+
+/// Imports bookmarks from XBEL document sent as request body.
+pub async fn import_xbel(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing XBEL bookmarks");
+
+    let folders =
+        xbel::parse(&body).map_err(|e| RequestError::Invalid("XBEL document", e.to_string()))?;
+    Ok(Json(importer::import_folders(&pool, &user, folders).await?))
+}
+
+/// Imports bookmarks from CSV spreadsheet sent as request body. Columns are mapped
+/// according to query parameters, eg. `?url=Link&name=Title&category=Folder&delimiter=;`.
+pub async fn import_csv(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(options): Query<CsvOptions>,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing CSV bookmarks");
+
+    let folders = csv::parse(&body, &options)
+        .map_err(|e| RequestError::Invalid("CSV spreadsheet", e.to_string()))?;
+    Ok(Json(importer::import_folders(&pool, &user, folders).await?))
+}
+
+/// Imports Flame dashboard sent as request body - either Flame's SQLite database file
+/// or JSON document with Flame's apps, categories and bookmarks.
+pub async fn import_flame(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: Bytes,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing Flame dashboard");
+
+    let dashboard = if flame::is_sqlite(&body) {
+        // SQLite can't read database from memory, it needs to land in a file first
+        let path = std::env::temp_dir().join(format!("flame-{}.sqlite", Uuid::new_v4()));
+        tokio::fs::write(&path, &body).await?;
+        let dashboard = flame::read_sqlite(&path).await;
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            tracing::warn!(error = ?e, "Couldn't remove Flame's database file");
+        }
+        dashboard.map_err(|e| RequestError::Invalid("Flame export", e.to_string()))?
+    } else {
+        let json = std::str::from_utf8(&body)
+            .map_err(|e| RequestError::Invalid("Flame export", e.to_string()))?;
+        flame::parse_json(json).map_err(|e| RequestError::Invalid("Flame export", e.to_string()))?
+    };
+    Ok(Json(
+        importer::import_dashboard(&pool, &user, dashboard).await?,
+    ))
+}
+
+/// Imports applications from Homer's `config.yml` sent as request body.
+pub async fn import_homer(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing Homer dashboard");
+
+    let dashboard =
+        homer::parse(&body).map_err(|e| RequestError::Invalid("Homer config", e.to_string()))?;
+    Ok(Json(
+        importer::import_dashboard(&pool, &user, dashboard).await?,
+    ))
+}
+
+/// Imports applications from Dashy's `conf.yml` sent as request body.
+pub async fn import_dashy(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing Dashy dashboard");
+
+    let dashboard =
+        dashy::parse(&body).map_err(|e| RequestError::Invalid("Dashy config", e.to_string()))?;
+    Ok(Json(
+        importer::import_dashboard(&pool, &user, dashboard).await?,
+    ))
+}
+
+/// Imports applications from Heimdall's JSON export sent as request body.
+pub async fn import_heimdall(
+    State(pool): State<SqlitePool>,
+    user: User,
+    body: String,
+) -> Result<Json<ImportReport>, ServiceError> {
+    tracing::info!("Importing Heimdall dashboard");
+
+    let dashboard = heimdall::parse(&body)
+        .map_err(|e| RequestError::Invalid("Heimdall export", e.to_string()))?;
+    Ok(Json(
+        importer::import_dashboard(&pool, &user, dashboard).await?,
+    ))
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::SqlitePool;
+
+use crate::{
+    errors::ServiceError,
+    extractors::ApiUser,
+    favicons::{self, IconTarget},
+    inbox::{self, QuickAdded, SharedLink},
+    models::bookmark::Bookmark,
+};
+
+/// Bookmarks shared link into user's inbox. Responds with the existing bookmark if the
+/// link's been bookmarked already.
+pub async fn quick_add(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Query(link): Query<SharedLink>,
+) -> Result<(StatusCode, Json<Bookmark>), ServiceError> {
+    tracing::info!(url = link.url, "Quickly adding new bookmark");
+    Ok(match inbox::quick_add(&pool, &user, link).await? {
+        QuickAdded::Created(bookmark) => {
+            favicons::spawn_favicon_discovery(
+                pool,
+                IconTarget::Bookmark(bookmark.id),
+                bookmark.url.clone(),
+            );
+            (StatusCode::CREATED, Json(bookmark))
+        }
+        QuickAdded::Existing(bookmark) => (StatusCode::OK, Json(bookmark)),
+    })
+}
@@ -0,0 +1,281 @@
+//! Linkding compatible API, so that linkding's browser extensions and mobile apps can be
+//! used with trufel. Bookmarks added this way land in user's inbox.
+
+use axum::{
+    extract::{Host, OriginalUri, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::{
+    errors::{RequestError, ServiceError},
+    extractors::ApiUser,
+    favicons::{self, IconTarget},
+    metadata::{self, PageMetadata},
+    models::{
+        linkding::{self, LinkdingBookmark, LinkdingBookmarkDetails, LinkdingTag},
+        user::User,
+    },
+};
+
+/// Number of results per page, unless a limit is requested.
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    #[serde(default)]
+    q: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+/// Page of results, linking the next and previous pages the way linkding does.
+#[derive(Serialize)]
+pub struct Page<T> {
+    count: usize,
+    next: Option<String>,
+    previous: Option<String>,
+    results: Vec<T>,
+}
+
+#[derive(Deserialize)]
+pub struct CheckQuery {
+    url: String,
+}
+
+#[derive(Serialize)]
+pub struct CheckMetadata {
+    url: String,
+    title: Option<String>,
+    description: Option<String>,
+    preview_image: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CheckResponse {
+    bookmark: Option<LinkdingBookmark>,
+    metadata: CheckMetadata,
+    auto_tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TagRequestPayload {
+    name: String,
+}
+
+/// Absolute URL of requested page, as seen by client.
+struct PageLocation {
+    base: String,
+}
+
+impl PageLocation {
+    fn new(headers: &HeaderMap, host: &str, uri: &OriginalUri) -> Self {
+        let scheme = headers
+            .get("x-forwarded-proto")
+            .and_then(|proto| proto.to_str().ok())
+            .unwrap_or("http");
+        PageLocation {
+            base: format!("{scheme}://{host}{}", uri.path()),
+        }
+    }
+
+    fn url(&self, query: &PageQuery, limit: usize, offset: usize) -> String {
+        let mut url = format!("{}?limit={limit}&offset={offset}", self.base);
+        if !query.q.is_empty() {
+            url.push_str("&q=");
+            url.extend(utf8_percent_encode(&query.q, NON_ALPHANUMERIC));
+        }
+        url
+    }
+}
+
+fn paginate<T>(items: Vec<T>, query: &PageQuery, location: &PageLocation) -> Page<T> {
+    let count = items.len();
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).max(1);
+    let offset = query.offset.unwrap_or_default();
+
+    Page {
+        count,
+        next: (offset + limit < count).then(|| location.url(query, limit, offset + limit)),
+        previous: (offset > 0).then(|| location.url(query, limit, offset.saturating_sub(limit))),
+        results: items.into_iter().skip(offset).take(limit).collect(),
+    }
+}
+
+async fn bookmarks_page(
+    pool: &SqlitePool,
+    user: &User,
+    archived: bool,
+    query: PageQuery,
+    location: PageLocation,
+) -> Result<Json<Page<LinkdingBookmark>>, ServiceError> {
+    let bookmarks = linkding::fetch_bookmarks(pool, user, archived, &query.q).await?;
+    Ok(Json(paginate(bookmarks, &query, &location)))
+}
+
+pub async fn bookmarks(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Query(query): Query<PageQuery>,
+    Host(host): Host,
+    uri: OriginalUri,
+    headers: HeaderMap,
+) -> Result<Json<Page<LinkdingBookmark>>, ServiceError> {
+    let location = PageLocation::new(&headers, &host, &uri);
+    bookmarks_page(&pool, &user, false, query, location).await
+}
+
+pub async fn archived_bookmarks(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Query(query): Query<PageQuery>,
+    Host(host): Host,
+    uri: OriginalUri,
+    headers: HeaderMap,
+) -> Result<Json<Page<LinkdingBookmark>>, ServiceError> {
+    let location = PageLocation::new(&headers, &host, &uri);
+    bookmarks_page(&pool, &user, true, query, location).await
+}
+
+pub async fn get_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+) -> Result<Json<LinkdingBookmark>, ServiceError> {
+    let bookmark = linkding::find_bookmark(&pool, &user, id)
+        .await?
+        .ok_or(RequestError::NotFound("Bookmark"))?;
+    Ok(Json(bookmark))
+}
+
+/// Tells whether given URL is bookmarked already, along with metadata of its page.
+pub async fn check_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Query(query): Query<CheckQuery>,
+) -> Result<Json<CheckResponse>, ServiceError> {
+    let bookmark = linkding::find_bookmark_by_url(&pool, &user, &query.url).await?;
+    let metadata = match &bookmark {
+        Some(bookmark) => CheckMetadata {
+            url: bookmark.url.clone(),
+            title: Some(bookmark.title.clone()),
+            description: None,
+            preview_image: None,
+        },
+        None => {
+            let page = metadata::page_metadata(&query.url)
+                .await
+                .unwrap_or_else(|_| PageMetadata {
+                    url: query.url.clone(),
+                    ..Default::default()
+                });
+            CheckMetadata {
+                url: page.url,
+                title: page.title,
+                description: page.description,
+                preview_image: page.image,
+            }
+        }
+    };
+    Ok(Json(CheckResponse {
+        bookmark,
+        metadata,
+        auto_tags: Vec::new(),
+    }))
+}
+
+/// Creates a new bookmark, or updates the one linking the same page. Linkding responds
+/// with `201 Created` in both cases.
+pub async fn add_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Json(details): Json<LinkdingBookmarkDetails>,
+) -> Result<(StatusCode, Json<LinkdingBookmark>), ServiceError> {
+    tracing::info!(
+        url = details.url,
+        "Adding new bookmark through linkding API"
+    );
+    let (bookmark, created) = linkding::create_bookmark(&pool, &user, details).await?;
+    if created {
+        favicons::spawn_favicon_discovery(
+            pool,
+            IconTarget::Bookmark(bookmark.bookmark_id),
+            bookmark.url.clone(),
+        );
+    }
+    Ok((StatusCode::CREATED, Json(bookmark)))
+}
+
+pub async fn update_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+    Json(details): Json<LinkdingBookmarkDetails>,
+) -> Result<Json<LinkdingBookmark>, ServiceError> {
+    Ok(Json(
+        linkding::update_bookmark(&pool, &user, id, details).await?,
+    ))
+}
+
+pub async fn archive_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ServiceError> {
+    linkding::set_archived(&pool, &user, id, true).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unarchive_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ServiceError> {
+    linkding::set_archived(&pool, &user, id, false).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn delete_bookmark(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ServiceError> {
+    linkding::delete_bookmark(&pool, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn tags(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Query(query): Query<PageQuery>,
+    Host(host): Host,
+    uri: OriginalUri,
+    headers: HeaderMap,
+) -> Result<Json<Page<LinkdingTag>>, ServiceError> {
+    let location = PageLocation::new(&headers, &host, &uri);
+    let tags = linkding::fetch_tags(&pool, &user).await?;
+    Ok(Json(paginate(tags, &query, &location)))
+}
+
+pub async fn get_tag(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Path(id): Path<i64>,
+) -> Result<Json<LinkdingTag>, ServiceError> {
+    let tag = linkding::find_tag(&pool, &user, id)
+        .await?
+        .ok_or(RequestError::NotFound("Tag"))?;
+    Ok(Json(tag))
+}
+
+pub async fn add_tag(
+    State(pool): State<SqlitePool>,
+    ApiUser(user): ApiUser,
+    Json(payload): Json<TagRequestPayload>,
+) -> Result<(StatusCode, Json<LinkdingTag>), ServiceError> {
+    let tag = linkding::create_tag(&pool, &user, &payload.name).await?;
+    Ok((StatusCode::CREATED, Json(tag)))
+}
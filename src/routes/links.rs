@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        application::Application,
+        bookmark::Bookmark,
+        link_check::{self, LinkReport},
+        user::User,
+    },
+};
+
+#[derive(Deserialize)]
+pub struct LinkFilter {
+    #[serde(default)]
+    broken: bool,
+}
+
+/// Lists results of recent checks of user's links, optionally only the broken ones.
+pub async fn links(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(filter): Query<LinkFilter>,
+) -> Result<Json<Vec<LinkReport>>, ServiceError> {
+    Ok(Json(
+        link_check::fetch_link_reports(&pool, &user, filter.broken).await?,
+    ))
+}
+
+pub async fn rewrite_bookmark_url(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    Ok(Json(
+        link_check::rewrite_bookmark_url(&pool, &user, &bookmark_id).await?,
+    ))
+}
+
+pub async fn rewrite_application_url(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        link_check::rewrite_application_url(&pool, &user, &application_id).await?,
+    ))
+}
@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    metadata::{self, PageMetadata},
+    models::{application::Application, bookmark::Bookmark, user::User},
+};
+
+#[derive(Deserialize)]
+pub struct MetadataQuery {
+    url: String,
+}
+
+/// Extracts metadata of given page, to prefill a new bookmark or application with.
+pub async fn page_metadata(
+    _user: User,
+    Query(query): Query<MetadataQuery>,
+) -> Result<Json<PageMetadata>, ServiceError> {
+    Ok(Json(metadata::page_metadata(&query.url).await?))
+}
+
+pub async fn refresh_bookmark_metadata(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Json<Bookmark>, ServiceError> {
+    Ok(Json(
+        metadata::refresh_bookmark_metadata(&pool, &user, &bookmark_id).await?,
+    ))
+}
+
+pub async fn refresh_application_metadata(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Application>, ServiceError> {
+    Ok(Json(
+        metadata::refresh_application_metadata(&pool, &user, &application_id).await?,
+    ))
+}
@@ -1,4 +1,23 @@
+pub mod applications;
+pub mod archives;
+pub mod bookmarks;
+pub mod bulk;
 pub mod components;
 pub mod categories;
+pub mod duplicates;
+pub mod exports;
+pub mod groups;
+pub mod icons;
+pub mod imports;
+pub mod inbox;
+pub mod linkding;
+pub mod links;
+pub mod metadata;
+pub mod opensearch;
 pub mod pusher;
+pub mod search;
+pub mod tags;
+pub mod tokens;
+pub mod trash;
 pub mod users;
+pub mod visits;
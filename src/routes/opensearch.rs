@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Host, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::{
+    errors::{AuthError, ServiceError},
+    extractors::SearchUser,
+    models::token::{self, TokenScope},
+    opensearch::{self, Suggestions},
+};
+
+#[derive(Deserialize)]
+pub struct DescriptionQuery {
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OpenSearchQuery {
+    #[serde(default)]
+    q: String,
+}
+
+/// Public URL of trufel, as set by `PUBLIC_URL` or as seen by client otherwise.
+fn public_url(headers: &HeaderMap, host: &str) -> String {
+    std::env::var("PUBLIC_URL").unwrap_or_else(|_| {
+        let scheme = headers
+            .get("x-forwarded-proto")
+            .and_then(|proto| proto.to_str().ok())
+            .unwrap_or("http");
+        format!("{scheme}://{host}")
+    })
+}
+
+/// Serves OpenSearch description. Description requested with a search-scoped API token
+/// makes browsers send the token along with their queries. Tokens of other scopes are
+/// rejected, so that they never end up in browsers' settings.
+pub async fn description(
+    State(pool): State<SqlitePool>,
+    Query(query): Query<DescriptionQuery>,
+    Host(host): Host,
+    headers: HeaderMap,
+) -> Result<Response, ServiceError> {
+    if let Some(secret) = &query.token {
+        if token::find_by_secret(&pool, secret, TokenScope::Search)
+            .await?
+            .is_none()
+        {
+            return Ok(AuthError::InvalidApiToken.into_response());
+        }
+    }
+    let description = opensearch::description(&public_url(&headers, &host), query.token.as_deref());
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "application/opensearchdescription+xml",
+        )],
+        description,
+    )
+        .into_response())
+}
+
+/// Redirects address-bar query to keyword's application or to trufel's search results.
+pub async fn search(
+    State(pool): State<SqlitePool>,
+    SearchUser(user): SearchUser,
+    Query(query): Query<OpenSearchQuery>,
+    Host(host): Host,
+    headers: HeaderMap,
+) -> Result<Response, ServiceError> {
+    let url = opensearch::resolve(&pool, &user, &query.q, &public_url(&headers, &host)).await?;
+    Ok((StatusCode::FOUND, [(header::LOCATION, url)]).into_response())
+}
+
+pub async fn suggestions(
+    State(pool): State<SqlitePool>,
+    SearchUser(user): SearchUser,
+    Query(query): Query<OpenSearchQuery>,
+) -> Result<Json<Suggestions>, ServiceError> {
+    Ok(Json(opensearch::suggest(&pool, &user, &query.q).await?))
+}
@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        search::{self, SearchHit},
+        user::User,
+        visit::SortOrder,
+    },
+};
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    q: String,
+    limit: Option<u32>,
+    #[serde(default)]
+    sort: SortOrder,
+}
+
+/// Full-text search of user's bookmarks, applications and categories.
+pub async fn search(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchHit>>, ServiceError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    Ok(Json(
+        search::search(&pool, &user, &query.q, limit, query.sort).await?,
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct DispatchQuery {
+    q: String,
+}
+
+/// Redirects "app-name terms" query into the named application's own search.
+pub async fn dispatch(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Query(query): Query<DispatchQuery>,
+) -> Result<Response, ServiceError> {
+    let url = search::dispatch(&pool, &user, &query.q).await?;
+    Ok((StatusCode::FOUND, [(header::LOCATION, url)]).into_response())
+}
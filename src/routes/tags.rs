@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::{RequestError, ServiceError},
+    models::{
+        application, bookmark,
+        tag::{self, Tag, TagSummary},
+        user::User,
+    },
+};
+
+/// Optional filter narrowing down listed bookmarks or applications to the tagged ones.
+#[derive(Deserialize)]
+pub struct TagFilter {
+    pub tag: Option<String>,
+}
+
+pub async fn tags(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<Vec<TagSummary>>, ServiceError> {
+    Ok(Json(tag::fetch_tags(&pool, &user).await?))
+}
+
+pub async fn delete_tag(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ServiceError> {
+    tag::delete_tag(&pool, &user, &name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn bookmark_tags(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    if bookmark::find_bookmark(&pool, &user, &bookmark_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Bookmark").into());
+    }
+    Ok(Json(
+        tag::fetch_bookmark_tags(&pool, &user, &bookmark_id).await?,
+    ))
+}
+
+pub async fn tag_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((bookmark_id, name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    Ok(Json(
+        tag::tag_bookmark(&pool, &user, &bookmark_id, &name).await?,
+    ))
+}
+
+pub async fn untag_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((bookmark_id, name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    Ok(Json(
+        tag::untag_bookmark(&pool, &user, &bookmark_id, &name).await?,
+    ))
+}
+
+pub async fn application_tags(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    if application::find_own_application(&pool, &user, &application_id)
+        .await?
+        .is_none()
+    {
+        return Err(RequestError::NotFound("Application").into());
+    }
+    Ok(Json(
+        tag::fetch_application_tags(&pool, &user, &application_id).await?,
+    ))
+}
+
+pub async fn tag_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((application_id, name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    Ok(Json(
+        tag::tag_application(&pool, &user, &application_id, &name).await?,
+    ))
+}
+
+pub async fn untag_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path((application_id, name)): Path<(Uuid, String)>,
+) -> Result<Json<Vec<Tag>>, ServiceError> {
+    Ok(Json(
+        tag::untag_application(&pool, &user, &application_id, &name).await?,
+    ))
+}
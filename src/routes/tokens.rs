@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        token::{self, ApiToken, ApiTokenDetails, NewApiToken},
+        user::User,
+    },
+};
+
+pub async fn tokens(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<Vec<ApiToken>>, ServiceError> {
+    Ok(Json(token::fetch_tokens(&pool, &user).await?))
+}
+
+/// Creates a new API token. Its secret is returned only this once.
+pub async fn add_token(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Json(details): Json<ApiTokenDetails>,
+) -> Result<(StatusCode, Json<NewApiToken>), ServiceError> {
+    let token = token::create_token(&pool, &user, details).await?;
+    Ok((StatusCode::CREATED, Json(token)))
+}
+
+pub async fn delete_token(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(token_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    token::delete_token(&pool, &user, &token_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
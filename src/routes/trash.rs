@@ -0,0 +1,90 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        trash::{self, TrashedItem},
+        user::User,
+    },
+};
+
+#[derive(Serialize)]
+pub struct PurgeResponse {
+    purged: u64,
+}
+
+pub async fn trash(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<Vec<TrashedItem>>, ServiceError> {
+    Ok(Json(trash::fetch_trash(&pool, &user).await?))
+}
+
+pub async fn empty_trash(
+    State(pool): State<SqlitePool>,
+    user: User,
+) -> Result<Json<PurgeResponse>, ServiceError> {
+    let purged = trash::purge_trash(&pool, Some(&user), 0).await?;
+    Ok(Json(PurgeResponse { purged }))
+}
+
+pub async fn restore_category(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(category_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::restore_category(&pool, &user, &category_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn purge_category(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(category_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::purge_category(&pool, &user, &category_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn restore_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::restore_bookmark(&pool, &user, &bookmark_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn purge_bookmark(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(bookmark_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::purge_bookmark(&pool, &user, &bookmark_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn restore_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::restore_application(&pool, &user, &application_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn purge_application(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(application_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    trash::purge_application(&pool, &user, &application_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    errors::ServiceError,
+    models::{
+        user::User,
+        visit::{self, SortOrder},
+    },
+};
+
+#[derive(Deserialize)]
+pub struct SortFilter {
+    #[serde(default)]
+    pub sort: SortOrder,
+}
+
+/// Records visit of bookmark or application. Webapp links to the item's URL directly and
+/// reports the visit aside, so that no credentials end up in followed links.
+pub async fn add_visit(
+    State(pool): State<SqlitePool>,
+    user: User,
+    Path(item_id): Path<Uuid>,
+) -> Result<StatusCode, ServiceError> {
+    visit::record_visit(&pool, &user, &item_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -70,10 +70,10 @@ pub fn make_span(request: &Request<Body>) -> tracing::Span {
         request.uri().clone()
     };
 
-    let http_target = uri
-        .path_and_query()
-        .map(|path_and_query| path_and_query.to_string())
-        .unwrap_or_else(|| uri.path().to_owned());
+    let http_target = match uri.query() {
+        Some(query) => format!("{}?{}", uri.path(), redact_query(query)),
+        None => uri.path().to_owned(),
+    };
 
     let (sentry_transaction_id, sentry_trace_id) = sentry_baggage(request);
     let (remote_context, trace_id) =
@@ -144,6 +144,22 @@ fn http_scheme(scheme: &Scheme) -> String {
     }
 }
 
+/// Query parameters never recorded in spans, as they carry credentials.
+const REDACTED_PARAMS: &[&str] = &["token"];
+
+/// Replaces values of credential-carrying parameters in query string, so API tokens
+/// sent along with a request don't end up in traces and logs.
+fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if REDACTED_PARAMS.contains(&name) => format!("{name}=REDACTED"),
+            _ => pair.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 // If remote request has no span data the propagator defaults to an unsampled context
 fn extract_remote_context(headers: &http::HeaderMap) -> opentelemetry::Context {
     struct HeaderExtractor<'a>(&'a http::HeaderMap);
@@ -221,3 +237,18 @@ fn sentry_baggage(request: &Request<Body>) -> (Option<Cow<str>>, Option<Cow<str>
     };
     (transaction_id, trace_id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_tokens_in_query() {
+        assert_eq!(
+            redact_query("url=https%3A%2F%2Fexample.com&token=secret&name=token"),
+            "url=https%3A%2F%2Fexample.com&token=REDACTED&name=token"
+        );
+        assert_eq!(redact_query("q=rust"), "q=rust");
+        assert_eq!(redact_query("token"), "token");
+    }
+}
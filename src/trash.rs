@@ -0,0 +1,38 @@
+//! Retention of trashed categories, bookmarks and applications. Items trashed for longer
+//! than retention period get purged periodically.
+//!
+//! Retention is configured with `TRASH_RETENTION_DAYS` environment variable (default: 30,
+//! 0 keeps trashed items until they're purged by user).
+
+use sqlx::SqlitePool;
+use std::time::Duration;
+
+use crate::models::trash;
+
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Seconds between purges of expired trash.
+const PURGE_INTERVAL: u64 = 60 * 60;
+
+/// Spawns background task purging expired trash periodically.
+pub fn spawn_trash_purger(pool: SqlitePool) {
+    let days = std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+    if days == 0 {
+        tracing::info!("Trash retention disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(PURGE_INTERVAL));
+        loop {
+            ticker.tick().await;
+            match trash::purge_trash(&pool, None, days).await {
+                Ok(purged) => tracing::debug!(purged, "Expired trash purged"),
+                Err(e) => tracing::error!(error = ?e, "Couldn't purge expired trash"),
+            }
+        }
+    });
+}
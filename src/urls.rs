@@ -0,0 +1,203 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::Url;
+
+use crate::errors::RequestError;
+
+/// Validates user-provided URL. Only absolute http(s) URLs with a host are accepted.
+pub fn validate_url(url: &str) -> Result<Url, RequestError> {
+    let parsed =
+        Url::parse(url.trim()).map_err(|e| RequestError::Invalid("url", format!("{url} ({e})")))?;
+
+    match parsed.scheme() {
+        "http" | "https" if parsed.host_str().is_some() => Ok(parsed),
+        _ => Err(RequestError::Invalid(
+            "url",
+            format!("{url} (only http and https links are supported)"),
+        )),
+    }
+}
+
+/// Query parameters which only track where link's been shared from, never affecting
+/// the page itself. Any `utm_*` parameter is a tracking one too.
+const TRACKING_PARAMETERS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc", "_hsmi",
+];
+
+fn is_tracking(parameter: &str) -> bool {
+    let name = parameter.split('=').next().unwrap_or_default();
+    let name = percent_encoding::percent_decode_str(name)
+        .decode_utf8_lossy()
+        .to_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMETERS.contains(&name.as_str())
+}
+
+/// Fragments of single page apps' routes (like `#/settings` or `#!/inbox`) point to
+/// different pages, unlike the ones pointing to sections of a page.
+fn is_route(fragment: &str) -> bool {
+    fragment.starts_with('/') || fragment.starts_with('!')
+}
+
+/// Canonicalizes URL, so that the same links get stored the same way. Parsing already
+/// lowercases scheme and host and drops default ports. On top of that, trailing dot of
+/// host, tracking parameters and empty query or fragment are dropped.
+pub fn canonicalize(mut url: Url) -> Url {
+    if let Some(host) = url.host_str().filter(|host| host.ends_with('.')) {
+        let host = host.trim_end_matches('.').to_string();
+        // host without the trailing dot is valid as long as the one with it was
+        let _ = url.set_host(Some(&host));
+    }
+    if let Some(query) = url.query() {
+        let query = query
+            .split('&')
+            .filter(|parameter| !parameter.is_empty() && !is_tracking(parameter))
+            .collect::<Vec<_>>()
+            .join("&");
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+    if url.fragment() == Some("") {
+        url.set_fragment(None);
+    }
+    url
+}
+
+/// Validates and canonicalizes user-provided URL.
+pub fn canonical_url(url: &str) -> Result<Url, RequestError> {
+    validate_url(url).map(canonicalize)
+}
+
+/// Key of URL which is the same for duplicate links - links differing only by trailing
+/// slash or fragment pointing to a section of the same page.
+pub fn duplicate_key(url: &str) -> String {
+    let Ok(mut url) = canonical_url(url) else {
+        return url.trim().to_string();
+    };
+    if !url.fragment().is_some_and(is_route) {
+        url.set_fragment(None);
+    }
+    if url.path().len() > 1 && url.path().ends_with('/') {
+        let path = url.path().trim_end_matches('/').to_string();
+        url.set_path(&path);
+    }
+    url.to_string()
+}
+
+/// Placeholder of searched terms within search URL templates.
+pub const SEARCH_TERMS: &str = "{q}";
+
+/// Characters of URL components which get percent-encoded - all but the unreserved ones.
+const COMPONENT_ENCODED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Validates search URL template, like `https://grafana/search?query={q}`. Template is
+/// kept as it is, as canonicalizing might percent-encode the placeholder.
+pub fn validate_search_url(template: &str) -> Result<String, RequestError> {
+    let template = template.trim();
+    if !template.contains(SEARCH_TERMS) {
+        return Err(RequestError::Invalid(
+            "search_url",
+            format!("{template} (no {SEARCH_TERMS} placeholder of searched terms)"),
+        ));
+    }
+    validate_url(&expand_search_url(template, "q"))
+        .map_err(|e| RequestError::Invalid("search_url", e.to_string()))?;
+    Ok(template.to_string())
+}
+
+/// Percent-encodes text to be a part of URL's query.
+pub fn encode_component(text: &str) -> String {
+    utf8_percent_encode(text, COMPONENT_ENCODED).to_string()
+}
+
+/// Expands search URL template with percent-encoded terms.
+pub fn expand_search_url(template: &str, terms: &str) -> String {
+    template.replace(SEARCH_TERMS, &encode_component(terms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(url: &str) -> String {
+        canonical_url(url).unwrap().to_string()
+    }
+
+    #[test]
+    fn rejects_non_http_urls() {
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("javascript:alert(1)").is_err());
+        assert!(validate_url("example.com").is_err());
+        assert!(validate_url(" https://example.com ").is_ok());
+    }
+
+    #[test]
+    fn canonicalizes_urls() {
+        assert_eq!(
+            canonical("HTTPS://Example.COM.:443/a"),
+            "https://example.com/a"
+        );
+        assert_eq!(
+            canonical("https://example.com/?utm_source=x&id=1&fbclid=y&&UTM_Medium=z"),
+            "https://example.com/?id=1"
+        );
+        assert_eq!(
+            canonical("https://example.com/?utm_source=x#"),
+            "https://example.com/"
+        );
+        // trailing slashes and fragments are kept, as pages might differ by them
+        assert_eq!(
+            canonical("https://example.com/a/#intro"),
+            "https://example.com/a/#intro"
+        );
+    }
+
+    #[test]
+    fn makes_same_key_of_duplicate_urls() {
+        assert_eq!(
+            duplicate_key("https://example.com/a/#intro"),
+            duplicate_key("https://EXAMPLE.com/a?utm_campaign=x")
+        );
+        assert_eq!(
+            duplicate_key("https://example.com/"),
+            "https://example.com/"
+        );
+        assert_ne!(
+            duplicate_key("http://example.com/a"),
+            duplicate_key("https://example.com/a")
+        );
+        // routes of single page apps are different pages
+        assert_ne!(
+            duplicate_key("https://app.example.com/#/inbox"),
+            duplicate_key("https://app.example.com/#/settings")
+        );
+        assert_eq!(duplicate_key(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn expands_search_urls() {
+        assert_eq!(
+            expand_search_url("https://github.com/search?q={q}&type=code", "rust axum"),
+            "https://github.com/search?q=rust%20axum&type=code"
+        );
+        assert_eq!(
+            expand_search_url("https://example.com/{q}", "a&b=c/d~e"),
+            "https://example.com/a%26b%3Dc%2Fd~e"
+        );
+        assert_eq!(
+            expand_search_url("https://example.com/?q={q}", "zażółć"),
+            "https://example.com/?q=za%C5%BC%C3%B3%C5%82%C4%87"
+        );
+    }
+
+    #[test]
+    fn validates_search_url_templates() {
+        assert_eq!(
+            validate_search_url(" https://grafana.local/search?query={q} ").unwrap(),
+            "https://grafana.local/search?query={q}"
+        );
+        assert!(validate_search_url("https://grafana.local/search").is_err());
+        assert!(validate_search_url("ftp://grafana.local/{q}").is_err());
+    }
+}